@@ -0,0 +1,38 @@
+//! Generates the solver registry from the day crates this crate depends on
+
+use aoc2020::day_and_part::day_and_part;
+use std::env;
+use std::fs;
+use std::path::Path;
+use toml_edit::DocumentMut;
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    let manifest = fs::read_to_string("Cargo.toml").expect("Manifest is readable");
+    let manifest = manifest
+        .parse::<DocumentMut>()
+        .expect("Manifest is valid TOML");
+
+    let mut days = manifest["dependencies"]
+        .as_table()
+        .expect("Manifest has dependencies")
+        .iter()
+        .filter_map(|(name, _)| day_and_part(name).map(|key| (key, name.to_string())))
+        .collect::<Vec<_>>();
+    days.sort();
+
+    let mut code = String::from("vec![\n");
+    for ((day, part), name) in days {
+        code.push_str(&format!(
+            "    Entry::new::<{}::Solution>(\"{}\", {}, {}),\n",
+            name.replace('-', "_"),
+            name,
+            day,
+            part
+        ));
+    }
+    code.push_str("]\n");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set for build scripts");
+    fs::write(Path::new(&out_dir).join("entries.rs"), code).expect("Registry is writable");
+}
@@ -0,0 +1,172 @@
+use crate::registry;
+use aoc2020::solver::Solver;
+use failure::{format_err, Error};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// File in the workspace directory where all benchmark results are appended
+pub const HISTORY_FILE: &str = "bench-history.tsv";
+
+/// A result is a regression if it is this much slower than the best previous result...
+const REGRESSION_FACTOR: f64 = 1.25;
+/// ...and the difference is larger than this, to avoid noise for fast solutions
+const REGRESSION_MARGIN: Duration = Duration::from_micros(200);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Timing {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Time reading and solving `input` once
+pub fn measure<S: Solver>(input: &str) -> Result<Timing, Error> {
+    let start = Instant::now();
+    let mut parsed = S::read_input(input.as_bytes())?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let output = S::solve(&mut parsed)?;
+    let solve = start.elapsed();
+    drop(output);
+
+    Ok(Timing { parse, solve })
+}
+
+/// The fastest parse and solve times over `runs` runs
+fn best_of(
+    measure: fn(&str) -> Result<Timing, Error>,
+    input: &str,
+    runs: usize,
+) -> Result<Timing, Error> {
+    let mut best: Option<Timing> = None;
+    for _ in 0..runs.max(1) {
+        let timing = measure(input)?;
+        best = Some(match best {
+            Some(best) => Timing {
+                parse: best.parse.min(timing.parse),
+                solve: best.solve.min(timing.solve),
+            },
+            None => timing,
+        });
+    }
+    Ok(best.expect("At least one run is made"))
+}
+
+/// A previous result for a solver
+struct Record {
+    name: String,
+    timing: Timing,
+}
+
+fn read_history(path: &Path) -> Result<Vec<Record>, Error> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut records = Vec::new();
+    for (index, line) in fs::read_to_string(path)?.lines().enumerate() {
+        let parts = line.split('\t').collect::<Vec<_>>();
+        if parts.len() != 4 {
+            return Err(format_err!(
+                "Malformed line {} in {}",
+                index + 1,
+                path.display()
+            ));
+        }
+        records.push(Record {
+            name: parts[1].to_string(),
+            timing: Timing {
+                parse: Duration::from_nanos(parts[2].parse()?),
+                solve: Duration::from_nanos(parts[3].parse()?),
+            },
+        });
+    }
+    Ok(records)
+}
+
+fn append_history(path: &Path, name: &str, timing: Timing) -> Result<(), Error> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(
+        file,
+        "{}\t{}\t{}\t{}",
+        timestamp,
+        name,
+        timing.parse.as_nanos(),
+        timing.solve.as_nanos()
+    )?;
+    Ok(())
+}
+
+fn is_regression(current: Duration, best: Duration) -> bool {
+    current.as_secs_f64() > best.as_secs_f64() * REGRESSION_FACTOR
+        && current > best + REGRESSION_MARGIN
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:9.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Options for the `bench` command
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Only run solvers whose name contains one of the filters
+    pub filters: Vec<String>,
+    pub runs: usize,
+    /// Do not append the results to the history file
+    pub dry_run: bool,
+}
+
+/// Benchmark the registered solvers on their puzzle input from the store
+///
+/// Returns the number of regressions found compared to the best results in the history.
+pub fn run(options: &Options) -> Result<usize, Error> {
+    let root = registry::root();
+    let history_path = root.join(HISTORY_FILE);
+    let history = read_history(&history_path)?;
+    let store = registry::store();
+
+    let mut regressions = 0;
+    for entry in registry::entries() {
+        if !options.filters.is_empty() && !options.filters.iter().any(|f| entry.name.contains(f)) {
+            continue;
+        }
+        let input = match store.input_path(entry.day, entry.part) {
+            Some(path) => fs::read_to_string(path)?,
+            None => {
+                println!("{:8} no puzzle input", entry.name);
+                continue;
+            }
+        };
+        let timing = best_of(entry.measure, &input, options.runs)?;
+
+        let previous = history.iter().filter(|record| record.name == entry.name);
+        let best_parse = previous.clone().map(|record| record.timing.parse).min();
+        let best_solve = previous.map(|record| record.timing.solve).min();
+        let mut notes = Vec::new();
+        if let Some(best) = best_parse.filter(|&best| is_regression(timing.parse, best)) {
+            notes.push(format!("parse regression, best{}", millis(best)));
+        }
+        if let Some(best) = best_solve.filter(|&best| is_regression(timing.solve, best)) {
+            notes.push(format!("solve regression, best{}", millis(best)));
+        }
+        if !notes.is_empty() {
+            regressions += 1;
+        }
+
+        println!(
+            "{:8} parse{} solve{} {}",
+            entry.name,
+            millis(timing.parse),
+            millis(timing.solve),
+            notes.join(", ")
+        );
+
+        if !options.dry_run {
+            append_history(&history_path, entry.name, timing)?;
+        }
+    }
+    Ok(regressions)
+}
@@ -0,0 +1,197 @@
+use aoc2020::store::Verdict;
+use failure::{bail, Error};
+use std::env;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::process;
+
+mod bench;
+mod registry;
+mod scaffold;
+
+const USAGE: &str = "Usage: aoc <command> [arguments]
+
+Commands:
+  bench [--runs N] [--dry-run] [filter...]
+        Time parsing and solving of each day's data/puzzle1.in, and compare
+        with the best results in the history file
+  new <day> <part> [--copy]
+        Create the crate dayN-M from the example-day template and add it to the
+        workspace and this runner. With --copy, part 2 starts from part 1
+  run <day> <part>
+        Solve the puzzle input and check the answer against the recorded answers
+  check <day> <part> <answer>
+        Check an answer against the recorded answers before submitting it
+  record <day> <part> <answer> <correct|too-high|too-low|wrong>
+        Record the verdict for a submitted answer
+  answers <day> <part>
+        List the recorded answers
+  input <day> <part> [file]
+        Save the puzzle input from the file, or from standard input
+  sample <day> <part> <file> [answer]
+        Add a sample input from the file, with its answer if known";
+
+fn bench(args: &[String]) -> Result<(), Error> {
+    let mut options = bench::Options {
+        runs: 5,
+        ..bench::Options::default()
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => match args.next().map(|runs| runs.parse()) {
+                Some(Ok(runs)) => options.runs = runs,
+                _ => bail!("--runs needs a number"),
+            },
+            "--dry-run" => options.dry_run = true,
+            _ if arg.starts_with('-') => bail!("Unknown option {}", arg),
+            _ => options.filters.push(arg.clone()),
+        }
+    }
+
+    let regressions = bench::run(&options)?;
+    if regressions > 0 {
+        bail!("{} solvers regressed", regressions);
+    }
+    Ok(())
+}
+
+/// The day and part from the first two arguments, and the remaining arguments
+fn puzzle(args: &[String]) -> Result<(u32, u32, &[String]), Error> {
+    if args.len() < 2 {
+        bail!("Give the day (1-25) and the part (1 or 2)\n\n{}", USAGE);
+    }
+    let (day, part) = (args[0].parse::<u32>()?, args[1].parse::<u32>()?);
+    if !(1..=25).contains(&day) || !(1..=2).contains(&part) {
+        bail!("Give the day (1-25) and the part (1 or 2)\n\n{}", USAGE);
+    }
+    Ok((day, part, &args[2..]))
+}
+
+fn read_file_or_stdin(path: Option<&String>) -> Result<String, Error> {
+    match path.map(String::as_str) {
+        Some("-") | None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            Ok(text)
+        }
+        Some(path) => Ok(fs::read_to_string(path)?),
+    }
+}
+
+fn new(args: &[String]) -> Result<(), Error> {
+    let copy = args.iter().any(|arg| arg == "--copy");
+    let args = args
+        .iter()
+        .filter(|arg| *arg != "--copy")
+        .cloned()
+        .collect::<Vec<_>>();
+    let (day, part, rest) = puzzle(&args)?;
+    if let Some(arg) = rest.first() {
+        bail!("Unexpected argument {}", arg);
+    }
+    let options = scaffold::Options { day, part, copy };
+
+    let directory = scaffold::run(&options)?;
+    println!("Created {}", directory.display());
+    println!(
+        "Add sample answers as data/*.out and run them with: cargo test -p {} --test samples",
+        options.name()
+    );
+    Ok(())
+}
+
+fn solve(args: &[String]) -> Result<(), Error> {
+    let (day, part, _) = puzzle(args)?;
+    let entry = registry::find(day, part)?;
+    let store = registry::store();
+    let answer = (entry.solve)(&store.input(day, part)?)?;
+    println!("{}", answer);
+    match store.ledger(day, part)?.check(&answer)? {
+        Some(verdict) => eprintln!("The answer is {}", verdict),
+        None => eprintln!("The answer has not been submitted"),
+    }
+    Ok(())
+}
+
+fn check(args: &[String]) -> Result<(), Error> {
+    let answer = match puzzle(args)? {
+        (day, part, [answer]) => registry::store().ledger(day, part)?.check(answer)?,
+        _ => bail!("Give the answer to check\n\n{}", USAGE),
+    };
+    match answer {
+        Some(verdict) => println!("The answer is {}", verdict),
+        None => println!("The answer may be right"),
+    }
+    Ok(())
+}
+
+fn record(args: &[String]) -> Result<(), Error> {
+    match puzzle(args)? {
+        (day, part, [answer, verdict]) => {
+            let verdict = verdict
+                .parse::<Verdict>()
+                .map_err(|_| failure::format_err!("Unknown verdict {}", verdict))?;
+            registry::store().record(day, part, answer, verdict)
+        }
+        _ => bail!("Give the answer and its verdict\n\n{}", USAGE),
+    }
+}
+
+fn answers(args: &[String]) -> Result<(), Error> {
+    let (day, part, _) = puzzle(args)?;
+    for guess in registry::store().ledger(day, part)?.guesses {
+        println!("{:8} {}", guess.verdict, guess.answer);
+    }
+    Ok(())
+}
+
+fn input(args: &[String]) -> Result<(), Error> {
+    let (day, part, rest) = puzzle(args)?;
+    let input = read_file_or_stdin(rest.first())?;
+    let path = registry::store().save_input(day, part, &input)?;
+    println!("Saved {}", path.display());
+    Ok(())
+}
+
+fn sample(args: &[String]) -> Result<(), Error> {
+    let (day, part, rest) = puzzle(args)?;
+    let input = read_file_or_stdin(rest.first())?;
+    let answer = rest.get(1).map(String::as_str);
+    let sample = registry::store().add_sample(day, part, &input, answer)?;
+    println!("Saved {}", sample.input.display());
+    Ok(())
+}
+
+fn run() -> Result<(), Error> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("bench") => bench(&args[1..]),
+        Some("new") => new(&args[1..]),
+        Some("run") => solve(&args[1..]),
+        Some("check") => check(&args[1..]),
+        Some("record") => record(&args[1..]),
+        Some("answers") => answers(&args[1..]),
+        Some("input") => input(&args[1..]),
+        Some("sample") => sample(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") | None => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => bail!("Unknown command {}\n\n{}", command, USAGE),
+    }
+}
+
+fn main() {
+    match run() {
+        Ok(()) => process::exit(0),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            for cause in error.iter_causes() {
+                eprintln!("{}", cause)
+            }
+            process::exit(1)
+        }
+    }
+}
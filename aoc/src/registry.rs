@@ -0,0 +1,59 @@
+use crate::bench;
+use crate::bench::Timing;
+use aoc2020::solver::Solver;
+use aoc2020::store::Store;
+use failure::{format_err, Error};
+use std::path::{Path, PathBuf};
+
+/// A registered solver for one part of a day
+pub struct Entry {
+    pub name: &'static str,
+    pub day: u32,
+    pub part: u32,
+    pub measure: fn(&str) -> Result<Timing, Error>,
+    pub solve: fn(&str) -> Result<String, Error>,
+}
+
+fn solve<S: Solver>(input: &str) -> Result<String, Error> {
+    Ok(S::solve_str(input)?.to_string())
+}
+
+impl Entry {
+    fn new<S: Solver>(name: &'static str, day: u32, part: u32) -> Entry {
+        Entry {
+            name,
+            day,
+            part,
+            measure: bench::measure::<S>,
+            solve: solve::<S>,
+        }
+    }
+}
+
+/// The workspace directory
+pub fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("The runner is in the workspace")
+        .to_path_buf()
+}
+
+/// All solvers, in day and part order
+///
+/// The list is generated from the day crates in the dependencies of this crate.
+pub fn entries() -> Vec<Entry> {
+    include!(concat!(env!("OUT_DIR"), "/entries.rs"))
+}
+
+/// The solver for a day and part
+pub fn find(day: u32, part: u32) -> Result<Entry, Error> {
+    entries()
+        .into_iter()
+        .find(|entry| entry.day == day && entry.part == part)
+        .ok_or_else(|| format_err!("There is no solver for day {} part {}", day, part))
+}
+
+/// The puzzle store of the workspace
+pub fn store() -> Store {
+    Store::new(root())
+}
@@ -0,0 +1,201 @@
+use crate::registry;
+use aoc2020::day_and_part::day_and_part;
+use aoc2020::samples::find_samples;
+use aoc2020::store;
+use failure::{bail, format_err, Error};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, Array, DocumentMut, InlineTable, Value};
+
+/// The crate every new day is modelled on, unless copying part 1
+pub const TEMPLATE: &str = "example-day";
+
+/// Options for the `new` command
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub day: u32,
+    pub part: u32,
+    /// Start part 2 from the solution and sample inputs of part 1
+    pub copy: bool,
+}
+
+impl Options {
+    pub fn name(&self) -> String {
+        crate_name(self.day, self.part)
+    }
+}
+
+pub fn crate_name(day: u32, part: u32) -> String {
+    format!("day{}-{}", day, part)
+}
+
+fn main_rs(name: &str) -> String {
+    format!(
+        "fn main() {{\n    aoc2020::solver::main::<{}::Solution>();\n}}\n",
+        name.replace('-', "_")
+    )
+}
+
+fn samples_rs(name: &str) -> String {
+    format!(
+        "fn main() {{\n    aoc2020::samples::main::<{}::Solution>(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/data\"));\n}}\n",
+        name.replace('-', "_")
+    )
+}
+
+/// Set the package name in the manifest of the template crate
+fn crate_manifest(template: &str, name: &str) -> Result<String, Error> {
+    let mut manifest = template.parse::<DocumentMut>()?;
+    manifest["package"]["name"] = value(name);
+    Ok(manifest.to_string())
+}
+
+/// Add `name` to the members of the workspace manifest, keeping days in order
+fn add_member(workspace: &str, name: &str) -> Result<String, Error> {
+    let mut manifest = workspace.parse::<DocumentMut>()?;
+    let members = manifest["workspace"]["members"]
+        .as_array_mut()
+        .ok_or_else(|| format_err!("Workspace manifest has no members array"))?;
+    if members.iter().any(|member| member.as_str() == Some(name)) {
+        bail!("{} is already a workspace member", name);
+    }
+
+    let key = day_and_part(name);
+    let mut names = members
+        .iter()
+        .filter_map(|member| member.as_str().map(str::to_string))
+        .collect::<Vec<_>>();
+    let index = names
+        .iter()
+        .position(|member| key.is_some() && day_and_part(member) > key)
+        .unwrap_or(names.len());
+    names.insert(index, name.to_string());
+
+    let mut result = Array::new();
+    for member in names {
+        result.push_formatted(Value::from(member).decorated("\n    ", ""));
+    }
+    result.set_trailing_comma(true);
+    result.set_trailing("\n");
+    *members = result;
+    Ok(manifest.to_string())
+}
+
+/// Add a path dependency on the crate `name` next to the runner
+fn add_dependency(runner: &str, name: &str) -> Result<String, Error> {
+    let mut manifest = runner.parse::<DocumentMut>()?;
+    let dependencies = manifest["dependencies"]
+        .as_table_mut()
+        .ok_or_else(|| format_err!("Runner manifest has no dependencies"))?;
+    let mut dependency = InlineTable::new();
+    dependency.insert("path", Value::from(format!("../{}", name)));
+    dependencies.insert(name, value(dependency));
+    Ok(manifest.to_string())
+}
+
+fn update<F>(path: &Path, edit: F) -> Result<(), Error>
+where
+    F: FnOnce(&str) -> Result<String, Error>,
+{
+    let contents = fs::read_to_string(path)?;
+    fs::write(path, edit(&contents)?)?;
+    Ok(())
+}
+
+/// Create the crate for a day and part, and register it in the workspace and the runner
+///
+/// The new crate gets the library of the template crate, a binary and a `samples` test
+/// that use it, and a `data/` directory with the sample inputs.
+pub fn run(options: &Options) -> Result<PathBuf, Error> {
+    let root = registry::root();
+    let name = options.name();
+    let directory = root.join(&name);
+    if directory.exists() {
+        bail!("{} exists already", directory.display());
+    }
+
+    let template = if options.copy {
+        if options.part != 2 {
+            bail!("Only part 2 can be copied from part 1");
+        }
+        crate_name(options.day, 1)
+    } else {
+        TEMPLATE.to_string()
+    };
+    let template = root.join(template);
+    if !template.is_dir() {
+        bail!("Template crate {} does not exist", template.display());
+    }
+
+    fs::create_dir_all(directory.join("src"))?;
+    fs::create_dir_all(directory.join("tests"))?;
+    fs::create_dir_all(directory.join("data"))?;
+
+    let manifest = fs::read_to_string(template.join("Cargo.toml"))?;
+    fs::write(
+        directory.join("Cargo.toml"),
+        crate_manifest(&manifest, &name)?,
+    )?;
+    fs::copy(template.join("src/lib.rs"), directory.join("src/lib.rs"))?;
+    fs::write(directory.join("src/main.rs"), main_rs(&name))?;
+    fs::write(directory.join("tests/samples.rs"), samples_rs(&name))?;
+
+    if options.copy {
+        // The answers for part 2 differ, so only the sample inputs are copied, and the
+        // store falls back to the puzzle input of part 1
+        for sample in find_samples(template.join("data"))? {
+            if sample.name != store::INPUT {
+                let file_name = sample.input.file_name().expect("Inputs have a name");
+                fs::copy(&sample.input, directory.join("data").join(file_name))?;
+            }
+        }
+    } else {
+        fs::write(directory.join("data/sample1.in"), "")?;
+    }
+
+    update(&root.join("Cargo.toml"), |manifest| {
+        add_member(manifest, &name)
+    })?;
+    update(&root.join("aoc/Cargo.toml"), |manifest| {
+        add_dependency(manifest, &name)
+    })?;
+
+    Ok(directory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_member() -> Result<(), Error> {
+        let workspace = "[workspace]\nmembers = [\n    \"aoc2020\",\n    \"day1-1\",    \"day10-1\",\n]\n\n[profile.release]\ndebug = true\n";
+        assert_eq!(
+            add_member(workspace, "day2-1")?,
+            "[workspace]\nmembers = [\n    \"aoc2020\",\n    \"day1-1\",\n    \"day2-1\",\n    \"day10-1\",\n]\n\n[profile.release]\ndebug = true\n"
+        );
+        assert!(add_member(workspace, "day1-1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_dependency() -> Result<(), Error> {
+        let runner =
+            "[package]\nname = \"aoc\"\n\n[dependencies]\nday1-1 = { path = \"../day1-1\"}\n";
+        assert_eq!(
+            add_dependency(runner, "day1-2")?,
+            "[package]\nname = \"aoc\"\n\n[dependencies]\nday1-1 = { path = \"../day1-1\"}\nday1-2 = { path = \"../day1-2\" }\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_crate_manifest() -> Result<(), Error> {
+        let template = "[package]\nname = \"example-day\"\nversion = \"0.1.0\"\n";
+        assert_eq!(
+            crate_manifest(template, "day3-2")?,
+            "[package]\nname = \"day3-2\"\nversion = \"0.1.0\"\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+use failure::{bail, Error};
+use std::collections::{BTreeSet, VecDeque};
+use std::iter::FromIterator;
+
+pub type Slot = usize;
+pub type Value = usize;
+
+/// The values each slot may take, where every slot takes a different value
+///
+/// Slots and values are dense ids, such as the ids from an `Interner`. There may be more
+/// values than slots, but not fewer.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::assign::Candidates;
+/// let candidates: Candidates = vec![vec![1, 2], vec![0, 1, 2], vec![2]].into_iter().collect();
+/// assert_eq!(candidates.solve()?, vec![1, 0, 2]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Candidates {
+    slots: Vec<BTreeSet<Value>>,
+}
+
+impl Candidates {
+    /// Candidates for `slots` slots, none of which have any values yet
+    pub fn new(slots: usize) -> Candidates {
+        Candidates {
+            slots: vec![BTreeSet::new(); slots],
+        }
+    }
+
+    /// The number of slots
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// The values that slot may take
+    pub fn values(&self, slot: Slot) -> &BTreeSet<Value> {
+        &self.slots[slot]
+    }
+
+    /// Let slot take value
+    pub fn allow(&mut self, slot: Slot, value: Value) {
+        self.slots[slot].insert(value);
+    }
+
+    /// Do not let slot take value
+    pub fn forbid(&mut self, slot: Slot, value: Value) {
+        self.slots[slot].remove(&value);
+    }
+
+    /// Only let slot take the values that are also in `values`
+    ///
+    /// This is the intersection used when each of several observations lists the possible
+    /// values of a slot.
+    pub fn restrict<I: IntoIterator<Item = Value>>(&mut self, slot: Slot, values: I) {
+        let values = values.into_iter().collect::<BTreeSet<_>>();
+        self.slots[slot].retain(|value| values.contains(value));
+    }
+
+    /// The value of each slot that has only one value left
+    pub fn fixed(&self) -> Vec<Option<Value>> {
+        self.slots
+            .iter()
+            .map(|values| {
+                if values.len() == 1 {
+                    values.iter().next().cloned()
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Remove the value of each slot with a single value from all other slots, until no
+    /// more values can be removed
+    ///
+    /// Fails if a slot is left without any values.
+    pub fn propagate(&mut self) -> Result<(), Error> {
+        let mut queue = (0..self.len())
+            .filter(|&slot| self.slots[slot].len() == 1)
+            .collect::<VecDeque<_>>();
+        while let Some(slot) = queue.pop_front() {
+            let value = match self.slots[slot].iter().next() {
+                Some(&value) => value,
+                None => bail!("No assignment exists, slot {} has no values left", slot),
+            };
+            for other in 0..self.len() {
+                if other != slot && self.slots[other].remove(&value) {
+                    match self.slots[other].len() {
+                        0 => bail!(
+                            "No assignment exists, slots {} and {} both need value {}",
+                            slot,
+                            other,
+                            value
+                        ),
+                        1 => queue.push_back(other),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        if let Some(slot) = self.slots.iter().position(BTreeSet::is_empty) {
+            bail!("No assignment exists, slot {} has no values", slot);
+        }
+        Ok(())
+    }
+
+    /// A largest set of slots with distinct values, using Hopcroft–Karp
+    ///
+    /// Every slot has a value if and only if some assignment exists.
+    pub fn matching(&self) -> Vec<Option<Value>> {
+        let values = self
+            .slots
+            .iter()
+            .filter_map(|values| values.iter().next_back())
+            .max()
+            .map_or(0, |&value| value + 1);
+        let mut matching = Matching {
+            slots: &self.slots,
+            slot_value: vec![None; self.len()],
+            value_slot: vec![None; values],
+            distance: vec![usize::MAX; self.len()],
+        };
+        while matching.layer() {
+            for slot in 0..self.len() {
+                if matching.slot_value[slot].is_none() {
+                    matching.augment(slot);
+                }
+            }
+        }
+        matching.slot_value
+    }
+
+    /// The only assignment of distinct values to all slots
+    ///
+    /// Slots with a single value are propagated first, which is enough for most puzzles.
+    /// Otherwise a matching shows whether any assignment exists, and a search for two
+    /// assignments shows whether it is unique. Fails when there is no assignment or more
+    /// than one.
+    pub fn solve(&self) -> Result<Vec<Value>, Error> {
+        let mut candidates = self.clone();
+        candidates.propagate()?;
+        let fixed = candidates.fixed();
+        if fixed.iter().all(Option::is_some) {
+            return Ok(fixed.into_iter().flatten().collect());
+        }
+
+        let matching = candidates.matching();
+        if let Some(slot) = matching.iter().position(Option::is_none) {
+            bail!(
+                "No assignment exists, only {} of the {} slots can get distinct values, \
+                 such as not slot {}",
+                matching.iter().flatten().count(),
+                self.len(),
+                slot
+            );
+        }
+
+        let mut solutions = Vec::new();
+        candidates.search(
+            &mut vec![None; self.len()],
+            &mut BTreeSet::new(),
+            &mut solutions,
+        );
+        match solutions.len() {
+            0 => bail!("No assignment exists"),
+            1 => Ok(solutions.remove(0)),
+            _ => {
+                let slot = (0..self.len())
+                    .find(|&slot| solutions[0][slot] != solutions[1][slot])
+                    .expect("Different solutions differ in some slot");
+                bail!(
+                    "Ambiguous assignment, slot {} can be both {} and {}",
+                    slot,
+                    solutions[0][slot],
+                    solutions[1][slot]
+                )
+            }
+        }
+    }
+
+    /// Backtracking search for up to two assignments, trying the slot with the fewest
+    /// unused values first
+    fn search(
+        &self,
+        assignment: &mut Vec<Option<Value>>,
+        used: &mut BTreeSet<Value>,
+        solutions: &mut Vec<Vec<Value>>,
+    ) {
+        if solutions.len() >= 2 {
+            return;
+        }
+        let next = (0..self.len())
+            .filter(|&slot| assignment[slot].is_none())
+            .map(|slot| {
+                let free = self.slots[slot]
+                    .iter()
+                    .filter(|value| !used.contains(value))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (slot, free)
+            })
+            .min_by_key(|(_, free)| free.len());
+        let (slot, free) = match next {
+            Some(next) => next,
+            None => {
+                solutions.push(assignment.iter().flatten().cloned().collect());
+                return;
+            }
+        };
+        for value in free {
+            assignment[slot] = Some(value);
+            used.insert(value);
+            self.search(assignment, used, solutions);
+            used.remove(&value);
+            assignment[slot] = None;
+        }
+    }
+}
+
+impl<I: IntoIterator<Item = Value>> FromIterator<I> for Candidates {
+    /// Collects the values of each slot in order
+    fn from_iter<T: IntoIterator<Item = I>>(slots: T) -> Self {
+        Candidates {
+            slots: slots
+                .into_iter()
+                .map(|values| values.into_iter().collect())
+                .collect(),
+        }
+    }
+}
+
+/// The state of the Hopcroft–Karp algorithm
+struct Matching<'a> {
+    slots: &'a [BTreeSet<Value>],
+    slot_value: Vec<Option<Value>>,
+    value_slot: Vec<Option<Slot>>,
+    /// The length of the shortest alternating path from an unmatched slot
+    distance: Vec<usize>,
+}
+
+impl<'a> Matching<'a> {
+    /// Compute the distances from unmatched slots, true if an augmenting path exists
+    fn layer(&mut self) -> bool {
+        let mut queue = VecDeque::new();
+        for slot in 0..self.slots.len() {
+            if self.slot_value[slot].is_none() {
+                self.distance[slot] = 0;
+                queue.push_back(slot);
+            } else {
+                self.distance[slot] = usize::MAX;
+            }
+        }
+        let mut found = false;
+        while let Some(slot) = queue.pop_front() {
+            for &value in &self.slots[slot] {
+                match self.value_slot[value] {
+                    None => found = true,
+                    Some(other) => {
+                        if self.distance[other] == usize::MAX {
+                            self.distance[other] = self.distance[slot] + 1;
+                            queue.push_back(other);
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Extend the matching along a shortest augmenting path from slot, if there is one
+    fn augment(&mut self, slot: Slot) -> bool {
+        for &value in self.slots[slot].iter() {
+            let free = match self.value_slot[value] {
+                None => true,
+                Some(other) => {
+                    self.distance[other] == self.distance[slot] + 1 && self.augment(other)
+                }
+            };
+            if free {
+                self.slot_value[slot] = Some(value);
+                self.value_slot[value] = Some(slot);
+                return true;
+            }
+        }
+        self.distance[slot] = usize::MAX;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Interner;
+
+    #[test]
+    fn test_ticket_fields() -> Result<(), Error> {
+        let fields = [
+            ("class", [(0, 1), (4, 19)]),
+            ("row", [(0, 5), (8, 19)]),
+            ("seat", [(0, 13), (16, 19)]),
+        ];
+        let tickets = [[3, 9, 18], [15, 1, 5], [5, 14, 9]];
+        let mut candidates = Candidates::new(fields.len());
+        for (slot, (_, ranges)) in fields.iter().enumerate() {
+            for column in 0..3 {
+                if tickets.iter().all(|ticket| {
+                    ranges
+                        .iter()
+                        .any(|&(low, high)| low <= ticket[column] && ticket[column] <= high)
+                }) {
+                    candidates.allow(slot, column);
+                }
+            }
+        }
+        assert_eq!(candidates.solve()?, vec![1, 0, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_allergens() -> Result<(), Error> {
+        let foods = [
+            ("mxmxvkd kfcds sqjhc nhms", "dairy fish"),
+            ("trh fvjkl sbzzf mxmxvkd", "dairy"),
+            ("sqjhc fvjkl", "soy"),
+            ("sqjhc mxmxvkd sbzzf", "fish"),
+        ];
+        let mut ingredients = Interner::new();
+        let allergens = foods
+            .iter()
+            .flat_map(|(_, allergens)| allergens.split(' '))
+            .collect::<Interner>();
+        let mut candidates = Candidates::new(allergens.len());
+        let mut seen = vec![false; allergens.len()];
+        for (food, contains) in &foods {
+            let food = food
+                .split(' ')
+                .map(|ingredient| ingredients.intern(ingredient) as Value)
+                .collect::<Vec<_>>();
+            for allergen in contains.split(' ') {
+                let slot = allergens.get(allergen).unwrap() as Slot;
+                if seen[slot] {
+                    candidates.restrict(slot, food.iter().cloned());
+                } else {
+                    seen[slot] = true;
+                    food.iter().for_each(|&value| candidates.allow(slot, value));
+                }
+            }
+        }
+
+        let dangerous = candidates
+            .solve()?
+            .into_iter()
+            .map(|value| ingredients.resolve(value as u32))
+            .collect::<Vec<_>>();
+        assert_eq!(dangerous, vec!["mxmxvkd", "sqjhc", "fvjkl"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let solve = |slots: Vec<Vec<Value>>| {
+            let candidates = slots.into_iter().collect::<Candidates>();
+            candidates.solve().unwrap_err().to_string()
+        };
+        assert!(solve(vec![vec![0], vec![0, 1], vec![1]]).starts_with("No assignment"));
+        assert!(solve(vec![vec![0, 1], vec![0, 1], vec![0, 1]]).starts_with("No assignment"));
+        assert!(solve(vec![vec![], vec![0]]).starts_with("No assignment"));
+        assert_eq!(
+            solve(vec![vec![0, 1], vec![0, 1]]),
+            "Ambiguous assignment, slot 0 can be both 0 and 1"
+        );
+        assert!(
+            solve(vec![vec![2], vec![0, 1, 3], vec![1, 3], vec![0, 1]]).starts_with("Ambiguous")
+        );
+    }
+
+    #[test]
+    fn test_matching() {
+        let candidates: Candidates = vec![vec![0, 1], vec![0]].into_iter().collect();
+        assert_eq!(candidates.matching(), vec![Some(1), Some(0)]);
+
+        let candidates: Candidates = vec![vec![0, 1], vec![0], vec![1, 2], vec![2]]
+            .into_iter()
+            .collect();
+        let matching = candidates.matching();
+        let values = matching.iter().flatten().collect::<BTreeSet<_>>();
+        assert_eq!(values.len(), 3);
+        assert_eq!(matching.iter().filter(|value| value.is_none()).count(), 1);
+        for (slot, value) in matching.iter().enumerate() {
+            if let Some(value) = value {
+                assert!(candidates.values(slot).contains(value));
+            }
+        }
+    }
+}
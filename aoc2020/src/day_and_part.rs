@@ -0,0 +1,16 @@
+//! Naming of the day crates, shared with the build script of the runner
+
+/// The day and part of a crate named like `day12-2`
+///
+/// # Examples
+///
+/// ```
+/// use aoc2020::day_and_part::day_and_part;
+/// assert_eq!(day_and_part("day12-2"), Some((12, 2)));
+/// assert_eq!(day_and_part("example-day"), None);
+/// ```
+pub fn day_and_part(name: &str) -> Option<(u32, u32)> {
+    let rest = name.strip_prefix("day")?;
+    let (day, part) = rest.split_once('-')?;
+    Some((day.parse().ok()?, part.parse().ok()?))
+}
@@ -6,7 +6,12 @@
     clippy::char_lit_as_u8
 )]
 
+use crate::grid::{cell_terminator, display_cells, GridLike};
+use crate::matrix::Matrix;
 use crate::position::*;
+use crate::ray::Ray;
+use crate::sparse_grid;
+use failure::Error;
 use hashbrown::HashMap;
 use std::cmp::max;
 use std::cmp::min;
@@ -15,6 +20,7 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 use std::ops::Index;
 use std::ops::IndexMut;
+use std::str::FromStr;
 use strum_macros::EnumString;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -72,11 +78,13 @@ where
             && position.y <= self.max_y
     }
 
-    fn index(&self, position: Position) -> usize {
-        if self.min_x == 0 && self.min_y == 0 {
-            position.y as usize * self.width + position.x as usize
+    fn index(&self, position: Position) -> Option<usize> {
+        if self.in_bounds(position) {
+            let x = (position.x - self.min_x) as usize;
+            let y = (position.y - self.min_y) as usize;
+            Some(y * self.width + x)
         } else {
-            panic!("Unhandled case right now");
+            None
         }
     }
 
@@ -87,7 +95,7 @@ where
                 position, self.min_x, self.max_x, self.min_y, self.max_y
             ));
         }
-        let index = self.index(position);
+        let index = self.index(position).unwrap();
         self.values[index] = value;
     }
 
@@ -95,16 +103,119 @@ where
     where
         I: Into<Position>,
     {
-        self.values.get(self.index(position.into()))
+        let index = self.index(position.into())?;
+        self.values.get(index)
     }
 
     pub fn get_mut<I>(&mut self, position: I) -> Option<&mut T>
     where
         I: Into<Position>,
     {
-        let index = self.index(position.into());
+        let index = self.index(position.into())?;
         self.values.get_mut(index)
     }
+
+    /// The positions seen when walking from `from` (exclusive) in `direction`
+    ///
+    /// The walk stops when leaving the grid.
+    pub fn ray<D>(&self, from: Position, direction: D) -> Ray<D>
+    where
+        D: Copy,
+        Position: Step<D>,
+    {
+        Ray::new(
+            from,
+            direction,
+            Position::new(self.min_x, self.min_y),
+            Position::new(self.max_x, self.max_y),
+        )
+    }
+
+    /// The first non-empty cell seen when looking from `from` (exclusive) in `direction`
+    ///
+    /// Returns `None` if only empty cells are seen before leaving the grid.
+    pub fn first_visible<D, F>(
+        &self,
+        from: Position,
+        direction: D,
+        is_empty: F,
+    ) -> Option<(Position, &T)>
+    where
+        D: Copy,
+        Position: Step<D>,
+        F: Fn(&T) -> bool,
+    {
+        self.ray(from, direction)
+            .map(|position| (position, &self[position]))
+            .find(|(_, value)| !is_empty(value))
+    }
+
+    /// The first non-empty cell in each of the eight directions from `from`
+    ///
+    /// Directions are checked in the same order as `connect8`, directions where no
+    /// non-empty cell is seen are skipped.
+    pub fn visible_from<'a, F>(
+        &'a self,
+        from: Position,
+        is_empty: F,
+    ) -> impl Iterator<Item = (Position, &'a T)> + 'a
+    where
+        F: Fn(&T) -> bool + 'a,
+    {
+        connect8(Position::origo())
+            .filter_map(move |direction| self.first_visible(from, direction, &is_empty))
+    }
+}
+
+impl<T> GridLike for Grid<T>
+where
+    T: Debug + Clone + Eq + Default,
+{
+    type Item = T;
+
+    fn get(&self, position: Position) -> Option<&T> {
+        Grid::get(self, position)
+    }
+
+    fn set(&mut self, position: Position, value: T) {
+        self.insert(position, value);
+    }
+
+    fn bounds(&self) -> (Position, Position) {
+        (
+            Position::new(self.min_x, self.min_y),
+            Position::new(self.max_x, self.max_y),
+        )
+    }
+
+    fn in_bounds(&self, position: Position) -> bool {
+        Grid::in_bounds(self, position)
+    }
+}
+
+/// Positions without a value in the sparse grid get the default value
+impl<T> From<&sparse_grid::Grid<T>> for Grid<T>
+where
+    T: Debug + Clone + Eq + Default,
+{
+    fn from(sparse: &sparse_grid::Grid<T>) -> Self {
+        let (min_pos, max_pos) = sparse.bounds();
+        let mut grid = Grid::new(min_pos.x, min_pos.y, max_pos.x, max_pos.y);
+        for (position, value) in sparse.iter() {
+            grid.insert(position, value.clone());
+        }
+        grid
+    }
+}
+
+impl From<&Matrix> for Grid<bool> {
+    fn from(matrix: &Matrix) -> Self {
+        let mut grid = Grid::from_origo(matrix.width, matrix.height);
+        for position in matrix.positions() {
+            grid.insert(position, matrix[(position.x as usize, position.y as usize)]);
+        }
+        grid
+    }
 }
 
 impl<T> Index<Position> for Grid<T>
@@ -167,6 +278,8 @@ where
     }
 }
 
+/// Values are padded to the widest value, and each followed by a space when wider than one
+/// character, so that the output can be parsed back with `FromStr`
 impl<T> Display for Grid<T>
 where
     T: Display + Debug + Clone + Eq + Default,
@@ -175,10 +288,11 @@ where
         let width = self
             .values
             .iter()
-            .map(|v| format!("{}", v).len())
+            .map(|v| format!("{}", v).chars().count())
             .max()
             .unwrap_or(1);
         let filler = " ".repeat(width);
+        let terminator = cell_terminator(width);
         for y in self.min_y..=self.max_y {
             for x in self.min_x..=self.max_x {
                 if let Some(v) = self.get((x, y)) {
@@ -186,9 +300,69 @@ where
                 } else {
                     write!(f, "{}", filler)?;
                 }
+                write!(f, "{}", terminator)?;
             }
             writeln!(f)?;
         }
         Ok(())
     }
 }
+
+/// Parses the `Display` output of a grid, with the minimum corner at (0, 0)
+impl<T> FromStr for Grid<T>
+where
+    T: FromStr + Debug + Clone + Eq + Default,
+    <T as FromStr>::Err: Display,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = display_cells(s);
+        let width = rows.first().map(Vec::len).unwrap_or(0);
+        if width == 0 {
+            return Ok(Grid::new(0, 0, -1, -1));
+        }
+        let mut grid = Grid::from_origo(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let value = cell.parse::<T>().map_err(|err| {
+                    failure::format_err!(
+                        "Could not parse \"{}\" at {}, reason is {}",
+                        cell,
+                        Position::from((x, y)),
+                        err
+                    )
+                })?;
+                grid[(x, y)] = value;
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_visible() -> Result<(), Error> {
+        let grid: Grid<char> = "#.#.\n....\n.L..\n....\n".parse()?;
+        let from = Position::new(2, 3);
+        let seat = |&c: &char| c == '.';
+        assert_eq!(
+            grid.first_visible(from, Compass::North, seat),
+            Some((Position::new(2, 0), &'#'))
+        );
+        assert_eq!(grid.first_visible(from, Compass::NorthEast, seat), None);
+        assert_eq!(grid.first_visible(from, Compass::South, seat), None);
+        assert_eq!(
+            grid.first_visible(from, Position::new(-1, -1), seat),
+            Some((Position::new(1, 2), &'L'))
+        );
+        assert_eq!(
+            grid.visible_from(from, seat).collect::<Vec<_>>(),
+            vec![(Position::new(2, 0), &'#'), (Position::new(1, 2), &'L')]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,477 @@
+use failure::{format_err, Error, Fail};
+use hashbrown::HashMap;
+use num::traits::CheckedRem;
+use num::PrimInt;
+use std::fmt;
+
+/// A binary operator
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+impl Operator {
+    fn from_char(ch: char) -> Option<Operator> {
+        match ch {
+            '+' => Some(Operator::Add),
+            '-' => Some(Operator::Subtract),
+            '*' => Some(Operator::Multiply),
+            '/' => Some(Operator::Divide),
+            '%' => Some(Operator::Remainder),
+            _ => None,
+        }
+    }
+
+    /// Apply the operator, with `None` on overflow or division by zero
+    fn apply<T: PrimInt + CheckedRem>(self, left: T, right: T) -> Option<T> {
+        match self {
+            Operator::Add => left.checked_add(&right),
+            Operator::Subtract => left.checked_sub(&right),
+            Operator::Multiply => left.checked_mul(&right),
+            Operator::Divide => left.checked_div(&right),
+            Operator::Remainder => left.checked_rem(&right),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+            Operator::Remainder => '%',
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TokenKind {
+    Number(u128),
+    Operator(Operator),
+    LeftParen,
+    RightParen,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Number(number) => write!(f, "{}", number),
+            TokenKind::Operator(operator) => write!(f, "{}", operator),
+            TokenKind::LeftParen => write!(f, "("),
+            TokenKind::RightParen => write!(f, ")"),
+        }
+    }
+}
+
+/// A token and the byte offset where it starts in the input
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub position: usize,
+}
+
+/// An error in the input, at the byte offset `position`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Fail for ParseError {}
+
+impl ParseError {
+    fn new<S: Into<String>>(position: usize, message: S) -> ParseError {
+        ParseError {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+/// Split the input into numbers, operators and parentheses, skipping whitespace
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((position, ch)) = chars.next() {
+        let kind = if ch.is_whitespace() {
+            continue;
+        } else if ch.is_ascii_digit() {
+            let mut end = position + 1;
+            while let Some(&(index, next)) = chars.peek() {
+                if !next.is_ascii_digit() {
+                    break;
+                }
+                end = index + 1;
+                chars.next();
+            }
+            let number = input[position..end]
+                .parse()
+                .map_err(|_| ParseError::new(position, "Number is too large"))?;
+            TokenKind::Number(number)
+        } else if ch == '(' {
+            TokenKind::LeftParen
+        } else if ch == ')' {
+            TokenKind::RightParen
+        } else if let Some(operator) = Operator::from_char(ch) {
+            TokenKind::Operator(operator)
+        } else {
+            return Err(ParseError::new(
+                position,
+                format!("Unexpected character '{}'", ch),
+            ));
+        };
+        tokens.push(Token { kind, position });
+    }
+    Ok(tokens)
+}
+
+/// How tightly each binary operator binds, higher levels binding tighter
+///
+/// All operators are left associative, and operators without a level are parse errors.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Precedence {
+    levels: HashMap<Operator, u8>,
+}
+
+impl Precedence {
+    /// A table without operators
+    pub fn new() -> Precedence {
+        Precedence::default()
+    }
+
+    /// Set the level of an operator
+    pub fn with(mut self, operator: Operator, level: u8) -> Precedence {
+        self.levels.insert(operator, level);
+        self
+    }
+
+    /// All operators bind equally tight, so evaluation is left to right
+    pub fn equal() -> Precedence {
+        Precedence::standard()
+            .with(Operator::Add, 2)
+            .with(Operator::Subtract, 2)
+    }
+
+    /// Addition and subtraction bind tighter than multiplication and division
+    pub fn addition_first() -> Precedence {
+        Precedence::standard()
+            .with(Operator::Add, 3)
+            .with(Operator::Subtract, 3)
+    }
+
+    /// The usual rules, with multiplication and division binding tighter
+    pub fn standard() -> Precedence {
+        Precedence::new()
+            .with(Operator::Add, 1)
+            .with(Operator::Subtract, 1)
+            .with(Operator::Multiply, 2)
+            .with(Operator::Divide, 2)
+            .with(Operator::Remainder, 2)
+    }
+
+    pub fn level(&self, operator: Operator) -> Option<u8> {
+        self.levels.get(&operator).cloned()
+    }
+}
+
+/// An expression tree
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Expr {
+    Number(u128),
+    Negate(Box<Expr>),
+    Binary(Operator, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluate the expression, with an error on overflow or division by zero
+    pub fn evaluate<T: PrimInt + CheckedRem>(&self) -> Result<T, Error> {
+        match self {
+            Expr::Number(number) => {
+                T::from(*number).ok_or_else(|| format_err!("{} does not fit the type", number))
+            }
+            Expr::Negate(expr) => {
+                let value = expr.evaluate::<T>()?;
+                T::zero()
+                    .checked_sub(&value)
+                    .ok_or_else(|| format_err!("Overflow in {}", self))
+            }
+            Expr::Binary(operator, left, right) => {
+                let left = left.evaluate::<T>()?;
+                let right = right.evaluate::<T>()?;
+                operator
+                    .apply(left, right)
+                    .ok_or_else(|| format_err!("Overflow or division by zero in {}", self))
+            }
+        }
+    }
+}
+
+/// Fully parenthesised, showing the structure of the expression
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(number) => write!(f, "{}", number),
+            Expr::Negate(expr) => write!(f, "-{}", expr),
+            Expr::Binary(operator, left, right) => write!(f, "({} {} {})", left, operator, right),
+        }
+    }
+}
+
+/// The deepest nesting of parentheses and negations, and the highest expression tree,
+/// that is parsed
+///
+/// Parsing, evaluating, displaying and dropping an expression all recurse through it, so
+/// deeper input, such as a long chain of additions, is an error instead of a stack
+/// overflow, even in debug builds.
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    index: usize,
+    end: usize,
+    /// The number of operands being parsed, one more than the parentheses and negations
+    /// around the current operand
+    depth: usize,
+    precedence: &'a Precedence,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.index).cloned()
+    }
+
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let token = self
+            .peek()
+            .ok_or_else(|| ParseError::new(self.end, "Unexpected end of input"))?;
+        self.index += 1;
+        Ok(token)
+    }
+
+    /// A number, a parenthesised expression, or a negated operand, with the height of its
+    /// tree
+    fn operand(&mut self) -> Result<(Expr, usize), ParseError> {
+        let token = self.next()?;
+        if self.depth >= MAX_DEPTH {
+            return Err(ParseError::new(
+                token.position,
+                format!("Nested more than {} levels deep", MAX_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        let result = self.nested_operand(token);
+        self.depth -= 1;
+        result
+    }
+
+    /// The operand starting with `token`
+    fn nested_operand(&mut self, token: Token) -> Result<(Expr, usize), ParseError> {
+        match token.kind {
+            TokenKind::Number(number) => Ok((Expr::Number(number), 1)),
+            TokenKind::Operator(Operator::Subtract) => {
+                let (expr, height) = self.operand()?;
+                Ok((Expr::Negate(Box::new(expr)), self.grow(height, token)?))
+            }
+            TokenKind::LeftParen => {
+                let result = self.expression(0)?;
+                match self.next()? {
+                    Token {
+                        kind: TokenKind::RightParen,
+                        ..
+                    } => Ok(result),
+                    token => Err(ParseError::new(
+                        token.position,
+                        format!("Expected ')', found '{}'", token.kind),
+                    )),
+                }
+            }
+            kind => Err(ParseError::new(
+                token.position,
+                format!("Unexpected '{}'", kind),
+            )),
+        }
+    }
+
+    /// The height of a tree with a subtree of height `height` below `token`, or an error if
+    /// it is too high
+    fn grow(&self, height: usize, token: Token) -> Result<usize, ParseError> {
+        if height >= MAX_DEPTH {
+            return Err(ParseError::new(
+                token.position,
+                format!("Expression is more than {} levels deep", MAX_DEPTH),
+            ));
+        }
+        Ok(height + 1)
+    }
+
+    /// An expression where all binary operators bind at least as tight as `min_level`, with
+    /// the height of its tree
+    fn expression(&mut self, min_level: u16) -> Result<(Expr, usize), ParseError> {
+        let (mut left, mut height) = self.operand()?;
+        while let Some(token) = self.peek() {
+            let operator = match token.kind {
+                TokenKind::Operator(operator) => operator,
+                _ => break,
+            };
+            let level = self.precedence.level(operator).ok_or_else(|| {
+                ParseError::new(
+                    token.position,
+                    format!("Operator '{}' has no precedence", operator),
+                )
+            })?;
+            if u16::from(level) < min_level {
+                break;
+            }
+            self.index += 1;
+            let (right, right_height) = self.expression(u16::from(level) + 1)?;
+            height = self.grow(height.max(right_height), token)?;
+            left = Expr::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok((left, height))
+    }
+}
+
+/// Parse an expression with the given operator precedence
+///
+/// # Examples
+///
+/// ```
+/// use aoc2020::expr::{parse, Precedence};
+/// let expr = parse("1 + 2 * 3", &Precedence::addition_first()).unwrap();
+/// assert_eq!(expr.to_string(), "((1 + 2) * 3)");
+/// assert_eq!(parse("1 + * 3", &Precedence::standard()).unwrap_err().position, 4);
+/// ```
+pub fn parse(input: &str, precedence: &Precedence) -> Result<Expr, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        index: 0,
+        end: input.len(),
+        depth: 0,
+        precedence,
+    };
+    let (expr, _) = parser.expression(0)?;
+    match parser.peek() {
+        Some(token) => Err(ParseError::new(
+            token.position,
+            format!("Unexpected '{}'", token.kind),
+        )),
+        None => Ok(expr),
+    }
+}
+
+/// Parse and evaluate an expression
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::expr::{evaluate, Precedence};
+/// assert_eq!(evaluate::<u64>("2 * 3 + (4 * 5)", &Precedence::equal())?, 26);
+/// assert_eq!(evaluate::<u64>("2 * 3 + (4 * 5)", &Precedence::addition_first())?, 46);
+/// assert_eq!(evaluate::<i128>("2 - 3 * 4", &Precedence::standard())?, -10);
+/// # Ok(())
+/// # }
+/// ```
+pub fn evaluate<T: PrimInt + CheckedRem>(input: &str, precedence: &Precedence) -> Result<T, Error> {
+    parse(input, precedence)?.evaluate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() -> Result<(), Error> {
+        let cases = [
+            ("1 + 2 * 3 + 4 * 5 + 6", 71, 231, 33),
+            ("1 + (2 * 3) + (4 * (5 + 6))", 51, 51, 51),
+            ("5 + (8 * 3 + 9 + 3 * 4 * 3)", 437, 1445, 74),
+            (
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                13632,
+                23340,
+                3208,
+            ),
+        ];
+        for &(input, equal, addition_first, standard) in &cases {
+            assert_eq!(evaluate::<u64>(input, &Precedence::equal())?, equal);
+            assert_eq!(
+                evaluate::<u64>(input, &Precedence::addition_first())?,
+                addition_first
+            );
+            assert_eq!(evaluate::<u64>(input, &Precedence::standard())?, standard);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_associativity_and_negation() -> Result<(), Error> {
+        let standard = Precedence::standard();
+        assert_eq!(parse("8 - 4 - 2", &standard)?.to_string(), "((8 - 4) - 2)");
+        assert_eq!(evaluate::<i128>("-(2 + 3) * -2", &standard)?, 10);
+        assert_eq!(evaluate::<i128>("7 % 4 - 10 / 3", &standard)?, 0);
+        assert!(evaluate::<u64>("-1", &standard).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        let standard = Precedence::standard();
+        let error = |input: &str| parse(input, &standard).unwrap_err();
+        assert_eq!(error("1 + x").position, 4);
+        assert_eq!(error("(1 + 2").position, 6);
+        assert_eq!(error("1 + 2)").position, 5);
+        assert_eq!(error("1 2").position, 2);
+        assert_eq!(error("").position, 0);
+        assert_eq!(
+            error("1 + x").to_string(),
+            "Unexpected character 'x' at position 4"
+        );
+        let additive = Precedence::new().with(Operator::Add, 1);
+        assert_eq!(parse("1 + 2 * 3", &additive).unwrap_err().position, 6);
+
+        assert!(evaluate::<u64>("1 / 0", &standard).is_err());
+        assert!(evaluate::<u8>("200 + 100", &standard).is_err());
+        assert!(evaluate::<u8>("300", &standard).is_err());
+        assert!(evaluate::<i8>("(0 - 127 - 1) % (0 - 1)", &standard).is_err());
+        assert!(evaluate::<i8>("5 % 0", &standard).is_err());
+    }
+
+    #[test]
+    fn test_limits() -> Result<(), Error> {
+        let highest = Precedence::new()
+            .with(Operator::Add, 255)
+            .with(Operator::Multiply, 0);
+        assert_eq!(evaluate::<u64>("1 + 2 * 3 + 4", &highest)?, 21);
+
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(evaluate::<u64>(&nested(MAX_DEPTH - 1), &highest)?, 1);
+        let error = parse(&nested(200_000), &highest).unwrap_err();
+        assert_eq!(error.position, MAX_DEPTH);
+        assert!(parse(&"-".repeat(200_000), &highest).is_err());
+
+        let chain = |terms: usize| vec!["1"; terms].join("+");
+        let expr = parse(&chain(MAX_DEPTH), &highest)?;
+        assert_eq!(expr.evaluate::<u64>()?, MAX_DEPTH as u64);
+        assert_eq!(parse(&expr.to_string(), &highest)?, expr);
+        let error = parse(&chain(MAX_DEPTH + 1), &highest).unwrap_err();
+        assert_eq!(error.position, 2 * MAX_DEPTH - 1);
+        assert!(evaluate::<u64>(&chain(100_000), &Precedence::standard()).is_err());
+        assert!(parse(&format!("{}+1", "-".repeat(MAX_DEPTH - 1)), &highest).is_err());
+        Ok(())
+    }
+}
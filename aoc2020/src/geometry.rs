@@ -384,6 +384,11 @@ impl LineSegment {
 #[cfg(test)]
 mod test {
     use crate::geometry::{Bounds, Intersections, LineSegment, Point2};
+    use proptest::prelude::*;
+
+    fn point() -> impl Strategy<Value = Point2> {
+        (-100.0..100.0f64, -100.0..100.0f64).prop_map(|(x, y)| Point2::new(x, y))
+    }
 
     #[test]
     fn test_bounds_overlap() {
@@ -474,4 +479,39 @@ mod test {
             );
         }
     }
+
+    proptest! {
+        #[test]
+        fn closest_point_is_on_segment(start in point(), end in point(), point in point()) {
+            let segment = LineSegment::new(start, end);
+            let closest = segment.closest_point_on_segment(point);
+            prop_assert!(segment.point_on_segment(closest));
+            let distance = (closest - point).norm();
+            prop_assert!(distance <= (start - point).norm() + 1e-9);
+            prop_assert!(distance <= (end - point).norm() + 1e-9);
+        }
+
+        #[test]
+        fn circle_intersections_are_on_circle_and_segment(
+            start in point(),
+            end in point(),
+            center in point(),
+            radius in 0.1..150.0f64
+        ) {
+            let segment = LineSegment::new(start, end);
+            for intersection in segment.circle_intersections(center, radius) {
+                prop_assert!(((intersection - center).norm() - radius).abs() < 1e-6);
+                prop_assert!(segment.point_on_segment(intersection));
+            }
+        }
+
+        #[test]
+        fn circle_around_midpoint_crosses_twice(start in point(), end in point(), fraction in 0.01..0.99f64) {
+            let segment = LineSegment::new(start, end);
+            prop_assume!(segment.length() > 1.0);
+            let midpoint = Point2::from((start.coords + end.coords) / 2.0);
+            let radius = fraction * segment.length() / 2.0;
+            prop_assert_eq!(segment.circle_intersections(midpoint, radius).len(), 2);
+        }
+    }
 }
@@ -0,0 +1,483 @@
+use failure::{bail, format_err, Error};
+use hashbrown::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+pub type RuleId = usize;
+
+/// A rule matches a literal text, or any one of a list of rule sequences
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Rule {
+    Terminal(String),
+    Alternatives(Vec<Vec<RuleId>>),
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    /// Parses the part after the colon, like `"a"` or `4 1 | 1 4`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+            return Ok(Rule::Terminal(s[1..s.len() - 1].to_string()));
+        }
+        let alternatives = s
+            .split('|')
+            .map(|sequence| {
+                sequence
+                    .split_whitespace()
+                    .map(|id| {
+                        id.parse()
+                            .map_err(|_| format_err!("Invalid rule reference \"{}\"", id))
+                    })
+                    .collect::<Result<Vec<RuleId>, Error>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if alternatives.iter().any(Vec::is_empty) {
+            bail!("Empty alternative in rule \"{}\"", s);
+        }
+        Ok(Rule::Alternatives(alternatives))
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Terminal(text) => write!(f, "\"{}\"", text),
+            Rule::Alternatives(alternatives) => {
+                for (index, sequence) in alternatives.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " |")?;
+                    }
+                    for (position, id) in sequence.iter().enumerate() {
+                        if index > 0 || position > 0 {
+                            write!(f, " ")?;
+                        }
+                        write!(f, "{}", id)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parse a numbered rule like `8: 42 | 42 8`
+pub fn parse_rule(line: &str) -> Result<(RuleId, Rule), Error> {
+    let mut parts = line.splitn(2, ':');
+    match (parts.next(), parts.next()) {
+        (Some(id), Some(rule)) => {
+            let id = id
+                .trim()
+                .parse()
+                .map_err(|_| format_err!("Invalid rule number in \"{}\"", line))?;
+            let rule = rule
+                .parse()
+                .map_err(|err| format_err!("Could not parse \"{}\": {}", line, err))?;
+            Ok((id, rule))
+        }
+        _ => bail!(
+            "Could not parse rule \"{}\", expected a number and a colon",
+            line
+        ),
+    }
+}
+
+/// Which rule matched which part of a message, with the rules it matched through
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Derivation {
+    pub rule: RuleId,
+    pub start: usize,
+    pub end: usize,
+    pub children: Vec<Derivation>,
+}
+
+impl Derivation {
+    /// The rules in the derivation, in pre-order
+    pub fn rules(&self) -> Vec<RuleId> {
+        let mut result = vec![self.rule];
+        for child in &self.children {
+            result.extend(child.rules());
+        }
+        result
+    }
+}
+
+/// A set of numbered rules
+///
+/// Rules may be recursive, also left recursive, such as `8: 8 42 | 42`.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::grammar::Grammar;
+/// let mut grammar: Grammar = "0: 1 2\n1: \"a\"\n2: \"b\"".parse()?;
+/// assert!(grammar.matches(0, "ab")?);
+/// assert!(!grammar.matches(0, "abbb")?);
+/// grammar.patch("2: 3 | 3 2\n3: \"b\"")?;
+/// assert!(grammar.matches(0, "abbb")?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Grammar {
+    rules: HashMap<RuleId, Rule>,
+}
+
+impl Grammar {
+    pub fn new() -> Grammar {
+        Grammar::default()
+    }
+
+    pub fn rule(&self, id: RuleId) -> Option<&Rule> {
+        self.rules.get(&id)
+    }
+
+    /// Add or replace a rule
+    pub fn set_rule(&mut self, id: RuleId, rule: Rule) {
+        self.rules.insert(id, rule);
+    }
+
+    /// Add or replace the rules given one per line, such as the changed rules of part 2
+    pub fn patch(&mut self, rules: &str) -> Result<(), Error> {
+        for line in rules.lines().filter(|line| !line.trim().is_empty()) {
+            let (id, rule) = parse_rule(line)?;
+            self.set_rule(id, rule);
+        }
+        Ok(())
+    }
+
+    /// The rule numbers in order
+    pub fn ids(&self) -> Vec<RuleId> {
+        let mut ids = self.rules.keys().cloned().collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    /// The positions where a match of rule `id` starting at `start` in `message` may end
+    pub fn match_ends(&self, id: RuleId, message: &str, start: usize) -> Result<Vec<usize>, Error> {
+        Matcher::new(self, message).ends(id, start)
+    }
+
+    /// True if rule `id` matches all of `message`
+    pub fn matches(&self, id: RuleId, message: &str) -> Result<bool, Error> {
+        Ok(self.match_ends(id, message, 0)?.contains(&message.len()))
+    }
+
+    /// How rule `id` matches all of `message`, if it does
+    pub fn derivation(&self, id: RuleId, message: &str) -> Result<Option<Derivation>, Error> {
+        let mut matcher = Matcher::new(self, message);
+        if !matcher.ends(id, 0)?.contains(&message.len()) {
+            return Ok(None);
+        }
+        let mut active = HashSet::new();
+        matcher.derive(id, 0, message.len(), &mut active)
+    }
+
+    /// The rules used in matching all of `message` with rule `id`, in order, if it matches
+    pub fn matched_rules(&self, id: RuleId, message: &str) -> Result<Option<Vec<RuleId>>, Error> {
+        Ok(self.derivation(id, message)?.map(|derivation| {
+            let mut rules = derivation.rules();
+            rules.sort();
+            rules.dedup();
+            rules
+        }))
+    }
+}
+
+impl FromStr for Grammar {
+    type Err = Error;
+
+    /// Parses rules like `0: 4 1 5`, `4: "a"`, or `1: 2 3 | 3 2`, one per line
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grammar = Grammar::new();
+        grammar.patch(s)?;
+        Ok(grammar)
+    }
+}
+
+/// The rules in order, one per line
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for id in self.ids() {
+            writeln!(f, "{}: {}", id, self.rules[&id])?;
+        }
+        Ok(())
+    }
+}
+
+/// The current result for a rule at a position that is being matched, and whether it
+/// was used by a recursive match
+struct Seed {
+    ends: Vec<usize>,
+    used: bool,
+}
+
+struct Matcher<'a> {
+    grammar: &'a Grammar,
+    message: &'a [u8],
+    seeds: HashMap<(RuleId, usize), Seed>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(grammar: &'a Grammar, message: &'a str) -> Matcher<'a> {
+        Matcher {
+            grammar,
+            message: message.as_bytes(),
+            seeds: HashMap::new(),
+        }
+    }
+
+    fn rule(&self, id: RuleId) -> Result<&'a Rule, Error> {
+        self.grammar
+            .rules
+            .get(&id)
+            .ok_or_else(|| format_err!("Rule {} is not defined", id))
+    }
+
+    /// The end positions of matches of rule `id` from `start`
+    ///
+    /// A rule that is reached again at the same position while being matched gets the
+    /// ends found so far, and the rule is matched again until no new ends are found.
+    /// This makes left recursion grow its matches instead of looping.
+    fn ends(&mut self, id: RuleId, start: usize) -> Result<Vec<usize>, Error> {
+        if let Some(seed) = self.seeds.get_mut(&(id, start)) {
+            seed.used = true;
+            return Ok(seed.ends.clone());
+        }
+        self.seeds.insert(
+            (id, start),
+            Seed {
+                ends: Vec::new(),
+                used: false,
+            },
+        );
+        let mut result = Vec::new();
+        loop {
+            let mut ends = self.rule_ends(id, start)?;
+            ends.extend(&result);
+            ends.sort();
+            ends.dedup();
+            let seed = self.seeds.get_mut(&(id, start)).expect("Seed is active");
+            if ends == result || !seed.used {
+                result = ends;
+                break;
+            }
+            result = ends;
+            seed.ends = result.clone();
+            seed.used = false;
+        }
+        self.seeds.remove(&(id, start));
+        Ok(result)
+    }
+
+    fn rule_ends(&mut self, id: RuleId, start: usize) -> Result<Vec<usize>, Error> {
+        match self.rule(id)? {
+            Rule::Terminal(text) => {
+                if self.message[start..].starts_with(text.as_bytes()) {
+                    Ok(vec![start + text.len()])
+                } else {
+                    Ok(Vec::new())
+                }
+            }
+            Rule::Alternatives(alternatives) => {
+                let mut result = Vec::new();
+                for sequence in alternatives {
+                    result.extend(self.sequence_ends(sequence, start)?);
+                }
+                Ok(result)
+            }
+        }
+    }
+
+    fn sequence_ends(&mut self, sequence: &[RuleId], start: usize) -> Result<Vec<usize>, Error> {
+        let mut positions = vec![start];
+        for &id in sequence {
+            let mut next = Vec::new();
+            for position in positions {
+                next.extend(self.ends(id, position)?);
+            }
+            next.sort();
+            next.dedup();
+            positions = next;
+        }
+        Ok(positions)
+    }
+
+    /// A derivation of rule `id` matching exactly `start..end`
+    fn derive(
+        &mut self,
+        id: RuleId,
+        start: usize,
+        end: usize,
+        active: &mut HashSet<(RuleId, usize, usize)>,
+    ) -> Result<Option<Derivation>, Error> {
+        if !active.insert((id, start, end)) {
+            return Ok(None);
+        }
+        let mut result = None;
+        match self.rule(id)? {
+            Rule::Terminal(text) => {
+                if start + text.len() == end && self.message[start..].starts_with(text.as_bytes()) {
+                    result = Some(Vec::new());
+                }
+            }
+            Rule::Alternatives(alternatives) => {
+                for sequence in alternatives {
+                    result = self.derive_sequence(sequence, start, end, active)?;
+                    if result.is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+        active.remove(&(id, start, end));
+        Ok(result.map(|children| Derivation {
+            rule: id,
+            start,
+            end,
+            children,
+        }))
+    }
+
+    fn derive_sequence(
+        &mut self,
+        sequence: &[RuleId],
+        start: usize,
+        end: usize,
+        active: &mut HashSet<(RuleId, usize, usize)>,
+    ) -> Result<Option<Vec<Derivation>>, Error> {
+        let (&first, rest) = match sequence.split_first() {
+            Some(split) => split,
+            None => return Ok(if start == end { Some(Vec::new()) } else { None }),
+        };
+        for middle in self.ends(first, start)? {
+            if middle > end {
+                continue;
+            }
+            if let Some(mut tail) = self.derive_sequence(rest, middle, end, active)? {
+                if let Some(head) = self.derive(first, start, middle, active)? {
+                    tail.insert(0, head);
+                    return Ok(Some(tail));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n";
+
+    #[test]
+    fn test_sample() -> Result<(), Error> {
+        let grammar = SAMPLE.parse::<Grammar>()?;
+        assert_eq!(grammar.to_string(), SAMPLE);
+        let matching = ["ababbb", "abbbab"];
+        let other = ["bababa", "aaabbb", "aaaabbb", ""];
+        for message in &matching {
+            assert!(grammar.matches(0, message)?, "{}", message);
+        }
+        for message in &other {
+            assert!(!grammar.matches(0, message)?, "{}", message);
+        }
+        assert_eq!(grammar.match_ends(3, "abba", 0)?, vec![2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursion() -> Result<(), Error> {
+        let mut grammar = "0: 1 0 2 | 1 2\n1: \"a\"\n2: \"b\"".parse::<Grammar>()?;
+        assert!(grammar.matches(0, "aaabbb")?);
+        assert!(!grammar.matches(0, "aaabb")?);
+
+        grammar.patch("0: 0 1 | 1")?;
+        assert!(grammar.matches(0, "aaaa")?);
+        assert!(!grammar.matches(0, "aaab")?);
+
+        grammar.patch("0: 3 | 1\n3: 0 2")?;
+        assert!(grammar.matches(0, "abbb")?);
+        assert!(grammar.matches(0, "a")?);
+        assert!(!grammar.matches(0, "ba")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_patched_loops() -> Result<(), Error> {
+        let rules = "42: 9 14 | 10 1\n9: 14 27 | 1 26\n10: 23 14 | 28 1\n1: \"a\"\n11: 42 31\n\
+                     5: 1 14 | 15 1\n19: 14 1 | 14 14\n12: 24 14 | 19 1\n16: 15 1 | 14 14\n\
+                     31: 14 17 | 1 13\n6: 14 14 | 1 14\n2: 1 24 | 14 4\n0: 8 11\n13: 14 3 | 1 12\n\
+                     15: 1 | 14\n17: 14 2 | 1 7\n23: 25 1 | 22 14\n28: 16 1\n4: 1 1\n\
+                     20: 14 14 | 1 15\n3: 5 14 | 16 1\n27: 1 6 | 14 18\n14: \"b\"\n21: 14 1 | 1 14\n\
+                     25: 1 1 | 1 14\n22: 14 14\n8: 42\n26: 14 22 | 1 20\n18: 15 15\n7: 14 5 | 1 21\n\
+                     24: 14 1";
+        let messages = [
+            "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa",
+            "bbabbbbaabaabba",
+            "babbbbaabbbbbabbbbbbaabaaabaaa",
+            "aaabbbbbbaaaabaababaabababbabaaabbababababaaa",
+            "bbbbbbbaaaabbbbaaabbabaaa",
+            "bbbababbbbaaaaaaaabbababaaababaabab",
+            "ababaaaaaabaaab",
+            "ababaaaaabbbaba",
+            "baabbaaaabbaaaababbaababb",
+            "abbbbabbbbaaaababbbbbbaaaababb",
+            "aaaaabbaabaaaaababaa",
+            "aaaabbaaaabbaaa",
+            "aaaabbaabbaaaaaaabbbabbbaaabbaabaaa",
+            "babaaabbbaaabaababbaabababaaab",
+            "aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba",
+        ];
+        let count = |grammar: &Grammar| -> Result<usize, Error> {
+            let mut count = 0;
+            for message in &messages {
+                if grammar.matches(0, message)? {
+                    count += 1;
+                }
+            }
+            Ok(count)
+        };
+
+        let mut grammar = rules.parse::<Grammar>()?;
+        assert_eq!(count(&grammar)?, 3);
+        grammar.patch("8: 42 | 42 8\n11: 42 31 | 42 11 31")?;
+        assert_eq!(count(&grammar)?, 12);
+        grammar.patch("8: 42 | 8 42")?;
+        assert_eq!(count(&grammar)?, 12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_derivation() -> Result<(), Error> {
+        let grammar = SAMPLE.parse::<Grammar>()?;
+        let derivation = grammar.derivation(0, "ababbb")?.unwrap();
+        assert_eq!(derivation.rules(), vec![0, 4, 1, 3, 5, 4, 2, 5, 5, 5]);
+        assert_eq!(derivation.children[1].start, 1);
+        assert_eq!(derivation.children[1].end, 5);
+        assert_eq!(
+            grammar.matched_rules(0, "abbbab")?,
+            Some(vec![0, 1, 2, 3, 4, 5])
+        );
+        assert_eq!(grammar.matched_rules(0, "bababa")?, None);
+
+        let grammar = "0: 0 1 | 1\n1: \"a\"".parse::<Grammar>()?;
+        let derivation = grammar.derivation(0, "aaa")?.unwrap();
+        assert_eq!(derivation.rules(), vec![0, 0, 0, 1, 1, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!("0 4 1".parse::<Grammar>().is_err());
+        assert!("x: 4 1".parse::<Grammar>().is_err());
+        assert!("0: 4 | ".parse::<Grammar>().is_err());
+        assert!("0: 4 x".parse::<Grammar>().is_err());
+        let grammar = "0: 1 2\n1: \"a\"".parse::<Grammar>().unwrap();
+        assert!(grammar.matches(0, "ab").is_err());
+    }
+}
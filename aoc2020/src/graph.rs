@@ -0,0 +1,358 @@
+use crate::Interner;
+use failure::{bail, format_err, Error};
+use hashbrown::{HashMap, HashSet};
+
+pub type NodeId = u32;
+pub type Weight = u64;
+
+/// A directed graph with named nodes and weighted edges
+///
+/// Nodes get consecutive ids in the order they are first seen, or the ids of an existing
+/// `Interner`. There may be several edges between the same two nodes, and both the
+/// outgoing and the incoming edges of a node are kept.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::graph::Graph;
+/// let mut graph = Graph::new();
+/// graph.add_edge("shiny gold", "dark olive", 1);
+/// graph.add_edge("shiny gold", "vibrant plum", 2);
+/// graph.add_edge("vibrant plum", "faded blue", 5);
+/// let gold = graph.id("shiny gold").unwrap();
+/// assert_eq!(graph.total_below(gold)?, 13);
+/// assert_eq!(graph.ancestors(graph.id("faded blue").unwrap()).len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    interner: Interner,
+    outgoing: Vec<Vec<(NodeId, Weight)>>,
+    incoming: Vec<Vec<(NodeId, Weight)>>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Mark {
+    Unvisited,
+    Active,
+    Done,
+}
+
+impl Graph {
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    /// A graph without edges that has a node for each name in `interner`
+    pub fn with_interner(interner: Interner) -> Graph {
+        let len = interner.len();
+        Graph {
+            interner,
+            outgoing: vec![Vec::new(); len],
+            incoming: vec![Vec::new(); len],
+        }
+    }
+
+    /// The names of the nodes
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    /// The number of nodes
+    pub fn len(&self) -> usize {
+        self.interner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interner.is_empty()
+    }
+
+    /// The id of the node called `name`, adding it if it is new
+    pub fn node(&mut self, name: &str) -> NodeId {
+        let id = self.interner.intern(name);
+        if id as usize == self.outgoing.len() {
+            self.outgoing.push(Vec::new());
+            self.incoming.push(Vec::new());
+        }
+        id
+    }
+
+    /// The id of the node called `name`, if there is one
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.interner.get(name)
+    }
+
+    /// The id of the node called `name`, or an error if there is none
+    pub fn require(&self, name: &str) -> Result<NodeId, Error> {
+        match self.id(name) {
+            Some(id) => Ok(id),
+            None => bail!("No node is called \"{}\"", name),
+        }
+    }
+
+    /// The name of node `id`
+    pub fn name(&self, id: NodeId) -> &str {
+        self.interner.resolve(id)
+    }
+
+    /// All node ids in order
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        0..self.len() as NodeId
+    }
+
+    /// Add an edge between two existing nodes
+    pub fn connect(&mut self, from: NodeId, to: NodeId, weight: Weight) {
+        assert!((from as usize) < self.len() && (to as usize) < self.len());
+        self.outgoing[from as usize].push((to, weight));
+        self.incoming[to as usize].push((from, weight));
+    }
+
+    /// Add an edge between two named nodes, adding the nodes if they are new
+    pub fn add_edge(&mut self, from: &str, to: &str, weight: Weight) {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.connect(from, to, weight);
+    }
+
+    /// The edges from node `id` as target and weight
+    pub fn outgoing(&self, id: NodeId) -> &[(NodeId, Weight)] {
+        &self.outgoing[id as usize]
+    }
+
+    /// The edges to node `id` as source and weight
+    pub fn incoming(&self, id: NodeId) -> &[(NodeId, Weight)] {
+        &self.incoming[id as usize]
+    }
+
+    /// The nodes that have a path to node `id`
+    ///
+    /// The node itself is only included if it is on a cycle.
+    pub fn ancestors(&self, id: NodeId) -> HashSet<NodeId> {
+        reachable(id, &self.incoming)
+    }
+
+    /// The nodes that node `id` has a path to
+    ///
+    /// The node itself is only included if it is on a cycle.
+    pub fn descendants(&self, id: NodeId) -> HashSet<NodeId> {
+        reachable(id, &self.outgoing)
+    }
+
+    /// The total weight of everything below node `id`
+    ///
+    /// Each edge counts its weight, and its weight times the total below its target, so a
+    /// bag holding 2 bags that each hold 3 bags has 2 + 2 * 3 bags below it. The total for
+    /// each node is only computed once.
+    pub fn total_below(&self, id: NodeId) -> Result<Weight, Error> {
+        let mut marks = vec![Mark::Unvisited; self.len()];
+        let mut order = Vec::new();
+        self.visit(id, &mut marks, &mut Vec::new(), &mut order)
+            .map_err(|cycle| self.cycle_error(&cycle))?;
+        let totals = self.totals(&order)?;
+        Ok(totals[&id])
+    }
+
+    /// The total weight below each node, indexed by node id
+    pub fn totals_below(&self) -> Result<Vec<Weight>, Error> {
+        let mut order = self.topological_sort()?;
+        order.reverse();
+        let totals = self.totals(&order)?;
+        Ok(self.nodes().map(|id| totals[&id]).collect())
+    }
+
+    /// Totals for the nodes in `order`, where every node comes after the nodes below it
+    fn totals(&self, order: &[NodeId]) -> Result<HashMap<NodeId, Weight>, Error> {
+        let mut totals: HashMap<NodeId, Weight> = HashMap::with_capacity(order.len());
+        for &node in order {
+            let mut total: Weight = 0;
+            for &(child, weight) in self.outgoing(node) {
+                total = totals[&child]
+                    .checked_add(1)
+                    .and_then(|below| below.checked_mul(weight))
+                    .and_then(|below| below.checked_add(total))
+                    .ok_or_else(|| {
+                        format_err!("The total weight below \"{}\" overflows", self.name(node))
+                    })?;
+            }
+            totals.insert(node, total);
+        }
+        Ok(totals)
+    }
+
+    /// The nodes ordered so that every edge goes from an earlier to a later node
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, Error> {
+        let mut marks = vec![Mark::Unvisited; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+        for id in self.nodes() {
+            self.visit(id, &mut marks, &mut Vec::new(), &mut order)
+                .map_err(|cycle| self.cycle_error(&cycle))?;
+        }
+        order.reverse();
+        Ok(order)
+    }
+
+    /// A cycle in the graph, starting and ending with the same node, if there is one
+    pub fn cycle(&self) -> Option<Vec<NodeId>> {
+        let mut marks = vec![Mark::Unvisited; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+        for id in self.nodes() {
+            if let Err(cycle) = self.visit(id, &mut marks, &mut Vec::new(), &mut order) {
+                return Some(cycle);
+            }
+        }
+        None
+    }
+
+    /// Depth first search from `id`, adding each node to `order` after all nodes below it
+    ///
+    /// Reaching a node that is on the current `path` gives the cycle as the error.
+    fn visit(
+        &self,
+        id: NodeId,
+        marks: &mut [Mark],
+        path: &mut Vec<NodeId>,
+        order: &mut Vec<NodeId>,
+    ) -> Result<(), Vec<NodeId>> {
+        match marks[id as usize] {
+            Mark::Done => return Ok(()),
+            Mark::Active => {
+                let start = path
+                    .iter()
+                    .position(|&node| node == id)
+                    .expect("Active nodes are on the path");
+                let mut cycle = path[start..].to_vec();
+                cycle.push(id);
+                return Err(cycle);
+            }
+            Mark::Unvisited => {}
+        }
+        marks[id as usize] = Mark::Active;
+        path.push(id);
+        for &(child, _) in self.outgoing(id) {
+            self.visit(child, marks, path, order)?;
+        }
+        path.pop();
+        marks[id as usize] = Mark::Done;
+        order.push(id);
+        Ok(())
+    }
+
+    fn cycle_error(&self, cycle: &[NodeId]) -> Error {
+        let names = cycle
+            .iter()
+            .map(|&id| format!("\"{}\"", self.name(id)))
+            .collect::<Vec<_>>();
+        format_err!("The graph has a cycle {}", names.join(" -> "))
+    }
+}
+
+/// The nodes reachable from `start` through at least one edge
+fn reachable(start: NodeId, edges: &[Vec<(NodeId, Weight)>]) -> HashSet<NodeId> {
+    let mut result = HashSet::new();
+    let mut queue = vec![start];
+    while let Some(node) = queue.pop() {
+        for &(next, _) in &edges[node as usize] {
+            if result.insert(next) {
+                queue.push(next);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bags(rules: &[(&str, &[(Weight, &str)])]) -> Graph {
+        let mut graph = Graph::new();
+        for &(outer, contents) in rules {
+            graph.node(outer);
+            for &(count, inner) in contents {
+                graph.add_edge(outer, inner, count);
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn test_bags() -> Result<(), Error> {
+        let graph = bags(&[
+            ("light red", &[(1, "bright white"), (2, "muted yellow")]),
+            ("dark orange", &[(3, "bright white"), (4, "muted yellow")]),
+            ("bright white", &[(1, "shiny gold")]),
+            ("muted yellow", &[(2, "shiny gold"), (9, "faded blue")]),
+            ("shiny gold", &[(1, "dark olive"), (2, "vibrant plum")]),
+            ("dark olive", &[(3, "faded blue"), (4, "dotted black")]),
+            ("vibrant plum", &[(5, "faded blue"), (6, "dotted black")]),
+            ("faded blue", &[]),
+            ("dotted black", &[]),
+        ]);
+        let gold = graph.require("shiny gold")?;
+        assert_eq!(graph.len(), 9);
+        assert_eq!(graph.ancestors(gold).len(), 4);
+        assert_eq!(graph.descendants(gold).len(), 4);
+        assert_eq!(graph.incoming(gold).len(), 2);
+        assert_eq!(graph.total_below(gold)?, 32);
+        assert_eq!(graph.totals_below()?[gold as usize], 32);
+        assert!(graph.require("plaid purple").is_err());
+
+        let again = Graph::with_interner(graph.interner().clone());
+        assert_eq!(again.require("shiny gold")?, gold);
+        assert!(again.outgoing(gold).is_empty());
+
+        let order = graph.topological_sort()?;
+        assert_eq!(order.len(), graph.len());
+        let position = |id: NodeId| order.iter().position(|&node| node == id).unwrap();
+        for from in graph.nodes() {
+            for &(to, _) in graph.outgoing(from) {
+                assert!(position(from) < position(to));
+            }
+        }
+        assert_eq!(graph.cycle(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deep_bags() -> Result<(), Error> {
+        let graph = bags(&[
+            ("shiny gold", &[(2, "dark red")]),
+            ("dark red", &[(2, "dark orange")]),
+            ("dark orange", &[(2, "dark yellow")]),
+            ("dark yellow", &[(2, "dark green")]),
+            ("dark green", &[(2, "dark blue")]),
+            ("dark blue", &[(2, "dark violet")]),
+            ("dark violet", &[]),
+        ]);
+        assert_eq!(graph.total_below(graph.require("shiny gold")?)?, 126);
+        assert_eq!(graph.total_below(graph.require("dark violet")?)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle() -> Result<(), Error> {
+        let mut graph = bags(&[
+            ("a", &[(1, "b")]),
+            ("b", &[(1, "c"), (2, "d")]),
+            ("c", &[(1, "a")]),
+        ]);
+        let a = graph.require("a")?;
+        let d = graph.require("d")?;
+        assert_eq!(graph.cycle(), Some(vec![0, 1, 2, 0]));
+        assert!(graph.ancestors(a).contains(&a));
+        assert_eq!(
+            graph.topological_sort().unwrap_err().to_string(),
+            "The graph has a cycle \"a\" -> \"b\" -> \"c\" -> \"a\""
+        );
+        assert!(graph.total_below(a).is_err());
+        assert_eq!(graph.total_below(d)?, 0);
+
+        graph.add_edge("d", "e", Weight::MAX);
+        graph.add_edge("d", "e", 1);
+        assert!(graph.total_below(d).is_err());
+        Ok(())
+    }
+}
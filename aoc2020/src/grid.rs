@@ -0,0 +1,319 @@
+use crate::position::*;
+use std::fmt::Debug;
+use std::fmt::Write;
+use strum_macros::EnumString;
+
+/// Which neighbours of a cell are considered adjacent
+#[derive(EnumString, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Connectivity {
+    /// Only the orthogonal neighbours, as in `connect`
+    Four,
+    /// Orthogonal and diagonal neighbours, as in `connect8`
+    Eight,
+}
+
+impl Connectivity {
+    /// The neighbours of `position`, without any bounds check
+    pub fn neighbours(self, position: Position) -> Vec<Position> {
+        match self {
+            Connectivity::Four => connect(position).collect(),
+            Connectivity::Eight => connect8(position).collect(),
+        }
+    }
+}
+
+/// Common interface for the grid-shaped containers that are indexed by `Position`
+///
+/// Implemented by `dense_grid::Grid`, `sparse_grid::Grid` and `Matrix`, so that
+/// algorithms working on cells only need to be written once.
+pub trait GridLike {
+    type Item;
+
+    /// The value at `position`, or `None` if there is no value there
+    fn get(&self, position: Position) -> Option<&Self::Item>;
+
+    /// Set the value at `position`
+    ///
+    /// Grids with fixed bounds panic if `position` is out of bounds.
+    fn set(&mut self, position: Position, value: Self::Item);
+
+    /// The inclusive bounds of the grid as the (min, max) corners
+    fn bounds(&self) -> (Position, Position);
+
+    fn in_bounds(&self, position: Position) -> bool {
+        let (min_pos, max_pos) = self.bounds();
+        min_pos.x <= position.x
+            && position.x <= max_pos.x
+            && min_pos.y <= position.y
+            && position.y <= max_pos.y
+    }
+
+    /// All positions that have a value, in reading order
+    fn positions(&self) -> Vec<Position> {
+        let (min_pos, max_pos) = self.bounds();
+        (min_pos.y..=max_pos.y)
+            .flat_map(|y| (min_pos.x..=max_pos.x).map(move |x| Position::new(x, y)))
+            .filter(|&position| self.get(position).is_some())
+            .collect()
+    }
+
+    /// The neighbours of `position` that are in bounds, with or without a value
+    fn neighbours(&self, position: Position, connectivity: Connectivity) -> Vec<Position> {
+        connectivity
+            .neighbours(position)
+            .into_iter()
+            .filter(|&neighbour| self.in_bounds(neighbour))
+            .collect()
+    }
+}
+
+/// What follows each cell in the `Display` output of a grid with cells `width` wide
+///
+/// Cells wider than one character each end with a space, also the last cell of a line, so
+/// that a line of wide cells can not be mistaken for single character cells.
+pub(crate) fn cell_terminator(width: usize) -> &'static str {
+    if width > 1 {
+        " "
+    } else {
+        ""
+    }
+}
+
+/// Split the `Display` output of a grid into the text of each cell, row by row
+///
+/// Cells are single characters when all values are one character wide. Otherwise each cell
+/// is padded to the widest value and followed by a single space, and the returned cell
+/// texts are trimmed. All rows are padded with empty cells to the same length.
+///
+/// Text where every line ends with a space, or where some line has whitespace between
+/// values, is read as wide cells. The second rule accepts hand written grids like
+/// `10 20` without the trailing spaces.
+pub(crate) fn display_cells(s: &str) -> Vec<Vec<String>> {
+    let lines = s
+        .lines()
+        .map(|line| line.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let terminated = lines
+        .iter()
+        .all(|line| line.last().is_some_and(|ch| ch.is_whitespace()));
+    let separated = terminated
+        || s.lines()
+            .any(|line| line.trim().contains(char::is_whitespace));
+    let width = if separated {
+        s.split_whitespace()
+            .map(|token| token.chars().count())
+            .max()
+            .unwrap_or(1)
+    } else {
+        1
+    };
+
+    let mut rows = lines
+        .iter()
+        .map(|line| {
+            if width == 1 {
+                line.iter().map(|ch| ch.to_string()).collect::<Vec<_>>()
+            } else {
+                line.chunks(width + 1)
+                    .map(|chunk| {
+                        let cell = &chunk[..chunk.len().min(width)];
+                        cell.iter().collect::<String>().trim().to_string()
+                    })
+                    .collect::<Vec<_>>()
+            }
+        })
+        .collect::<Vec<_>>();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(columns, String::new());
+    }
+    rows
+}
+
+/// Describe the cells that differ between two grids, or `None` if they are equal
+///
+/// Positions in the union of the bounds are compared, and a missing value only equals
+/// another missing value.
+pub fn diff<G, H>(expected: &G, actual: &H) -> Option<String>
+where
+    G: GridLike,
+    H: GridLike<Item = G::Item>,
+    G::Item: PartialEq + Debug,
+{
+    let describe = |value: Option<&G::Item>| match value {
+        Some(value) => format!("{:?}", value),
+        None => "nothing".to_string(),
+    };
+
+    let (expected_min, expected_max) = expected.bounds();
+    let (actual_min, actual_max) = actual.bounds();
+    let mut result = String::new();
+    if (expected_min, expected_max) != (actual_min, actual_max) {
+        writeln!(
+            result,
+            "bounds: expected {}-{}, found {}-{}",
+            expected_min, expected_max, actual_min, actual_max
+        )
+        .unwrap();
+    }
+    let min_pos = Position::new(
+        expected_min.x.min(actual_min.x),
+        expected_min.y.min(actual_min.y),
+    );
+    let max_pos = Position::new(
+        expected_max.x.max(actual_max.x),
+        expected_max.y.max(actual_max.y),
+    );
+    for y in min_pos.y..=max_pos.y {
+        for x in min_pos.x..=max_pos.x {
+            let position = Position::new(x, y);
+            let expected_value = expected.get(position);
+            let actual_value = actual.get(position);
+            if expected_value != actual_value {
+                writeln!(
+                    result,
+                    "{}: expected {}, found {}",
+                    position,
+                    describe(expected_value),
+                    describe(actual_value)
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Assert that two grids are equal, panicking with a cell-level diff if they are not
+pub fn assert_grid_eq<G, H>(expected: &G, actual: &H)
+where
+    G: GridLike,
+    H: GridLike<Item = G::Item>,
+    G::Item: PartialEq + Debug,
+{
+    if let Some(difference) = diff(expected, actual) {
+        panic!("Grids differ:\n{}", difference);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dense_grid;
+    use crate::matrix::Matrix;
+    use crate::sparse_grid;
+
+    fn count_true<G: GridLike<Item = bool>>(grid: &G) -> usize {
+        grid.positions()
+            .into_iter()
+            .filter(|&position| grid.get(position) == Some(&true))
+            .count()
+    }
+
+    #[test]
+    fn test_conversions() {
+        let matrix: Matrix = "#..#/.##./...#".parse().unwrap();
+        let dense = dense_grid::Grid::from(&matrix);
+        let sparse = sparse_grid::Grid::from(&matrix);
+        assert_eq!(count_true(&matrix), 5);
+        assert_eq!(count_true(&dense), 5);
+        assert_eq!(count_true(&sparse), 5);
+        assert_eq!(dense.bounds(), GridLike::bounds(&matrix));
+        assert_eq!(GridLike::bounds(&sparse), GridLike::bounds(&matrix));
+
+        assert_eq!(Matrix::from(&dense), matrix);
+        assert_eq!(Matrix::from(&sparse), matrix);
+        assert_eq!(dense_grid::Grid::from(&sparse), dense);
+        assert_eq!(sparse_grid::Grid::from(&dense), sparse);
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let matrix: Matrix = "#..#/.##./...#".parse().unwrap();
+        assert_eq!(matrix.to_string().parse::<Matrix>().unwrap(), matrix);
+
+        let mut numbers = dense_grid::Grid::from_origo(3, 2);
+        numbers[Position::new(0, 0)] = 1u32;
+        numbers[Position::new(1, 0)] = 10;
+        numbers[Position::new(2, 1)] = 110;
+        let text = numbers.to_string();
+        assert_eq!(text, "  1  10   0 \n  0   0 110 \n");
+        assert_grid_eq(&numbers, &text.parse::<dense_grid::Grid<u32>>().unwrap());
+
+        let mut chars = sparse_grid::Grid::new();
+        chars.insert(Position::new(0, 0), '#');
+        chars.insert(Position::new(2, 1), '.');
+        let text = chars.to_string();
+        assert_eq!(text, "#  \n  .\n");
+        assert_grid_eq(&chars, &text.parse::<sparse_grid::Grid<char>>().unwrap());
+
+        let mut spaced = dense_grid::Grid::from_origo(3, 1);
+        spaced[Position::new(0, 0)] = '#';
+        spaced[Position::new(1, 0)] = ' ';
+        spaced[Position::new(2, 0)] = '#';
+        let text = spaced.to_string();
+        assert_grid_eq(&spaced, &text.parse::<dense_grid::Grid<char>>().unwrap());
+
+        let digits = "12\n34\n".parse::<dense_grid::Grid<u32>>().unwrap();
+        assert_eq!((digits.width, digits.height), (2, 2));
+        assert_eq!(digits.to_string(), "12\n34\n");
+
+        let mut column = dense_grid::Grid::from_origo(1, 2);
+        column[Position::new(0, 0)] = 10u32;
+        column[Position::new(0, 1)] = 20;
+        let text = column.to_string();
+        assert_eq!(text, "10 \n20 \n");
+        assert_grid_eq(&column, &text.parse::<dense_grid::Grid<u32>>().unwrap());
+
+        let mut diagonal = sparse_grid::Grid::new();
+        diagonal.insert(Position::new(0, 0), 10u32);
+        diagonal.insert(Position::new(1, 1), 20);
+        let text = diagonal.to_string();
+        assert_eq!(text, "10    \n   20 \n");
+        assert_grid_eq(&diagonal, &text.parse::<sparse_grid::Grid<u32>>().unwrap());
+
+        assert!("1 x\n".parse::<dense_grid::Grid<u32>>().is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let expected: Matrix = "#./.#".parse().unwrap();
+        let actual: Matrix = "##/.#".parse().unwrap();
+        assert_eq!(diff(&expected, &expected), None);
+        assert_eq!(
+            diff(&expected, &actual),
+            Some("(1,0): expected false, found true\n".to_string())
+        );
+
+        let wider: Matrix = "#..".parse().unwrap();
+        assert_eq!(
+            diff(&wider, &expected).unwrap().lines().next(),
+            Some("bounds: expected (0,0)-(2,0), found (0,0)-(1,1)")
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let mut sparse = sparse_grid::Grid::new();
+        sparse.insert(Position::new(-1, -1), 1);
+        sparse.insert(Position::new(1, 1), 2);
+        let dense = dense_grid::Grid::from(&sparse);
+
+        let corner = Position::new(-1, -1);
+        assert_eq!(sparse.neighbours(corner, Connectivity::Four).len(), 2);
+        assert_eq!(sparse.neighbours(corner, Connectivity::Eight).len(), 3);
+        assert_eq!(
+            dense
+                .neighbours(Position::origo(), Connectivity::Eight)
+                .len(),
+            8
+        );
+        assert_eq!(dense.get(Position::origo()), Some(&0));
+        assert_eq!(GridLike::get(&sparse, Position::origo()), None);
+    }
+}
@@ -0,0 +1,260 @@
+use crate::grid::GridLike;
+use crate::position::*;
+use failure::Error;
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A colour with 8 bit red, green, and blue components
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb::new(0, 0, 0);
+    pub const WHITE: Rgb = Rgb::new(255, 255, 255);
+    pub const GREY: Rgb = Rgb::new(128, 128, 128);
+    pub const RED: Rgb = Rgb::new(255, 0, 0);
+    pub const GREEN: Rgb = Rgb::new(0, 255, 0);
+    pub const BLUE: Rgb = Rgb::new(0, 0, 255);
+    pub const YELLOW: Rgb = Rgb::new(255, 255, 0);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Rgb {
+        Rgb { r, g, b }
+    }
+}
+
+/// A raster image, typically rendered from a grid for debugging
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![Rgb::default(); width * height],
+        }
+    }
+
+    /// Render the bounds of `grid` with each cell as a `scale` times `scale` square
+    ///
+    /// The colour function is given `None` for positions without a value.
+    pub fn render<G, F>(grid: &G, scale: usize, colour: F) -> Image
+    where
+        G: GridLike,
+        F: Fn(Position, Option<&G::Item>) -> Rgb,
+    {
+        let (min_pos, max_pos) = grid.bounds();
+        let columns = (max_pos.x - min_pos.x + 1).max(0) as usize;
+        let rows = (max_pos.y - min_pos.y + 1).max(0) as usize;
+        let mut image = Image::new(columns * scale, rows * scale);
+        for row in 0..rows {
+            for column in 0..columns {
+                let position = min_pos + Position::from((column, row));
+                let cell_colour = colour(position, grid.get(position));
+                for y in row * scale..(row + 1) * scale {
+                    for x in column * scale..(column + 1) * scale {
+                        image.set(x, y, cell_colour);
+                    }
+                }
+            }
+        }
+        image
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        assert!(x < self.width && y < self.height);
+        self.pixels[x + y * self.width]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        assert!(x < self.width && y < self.height);
+        self.pixels[x + y * self.width] = colour;
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| vec![pixel.r, pixel.g, pixel.b])
+            .collect()
+    }
+
+    /// Write the image in the binary PPM (P6) format
+    pub fn write_ppm<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.bytes())?;
+        Ok(())
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_ppm(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Save the image as a PNG file, requires the `png` feature
+    #[cfg(feature = "png")]
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let writer = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.bytes())?;
+        Ok(())
+    }
+}
+
+/// The image formats that can be written to disk
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Format {
+    Ppm,
+    #[cfg(feature = "png")]
+    Png,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            #[cfg(feature = "png")]
+            Format::Png => "png",
+        }
+    }
+}
+
+/// Writes successive renderings of a grid as numbered image files
+///
+/// Useful for looking at the steps of a simulation, the files are named
+/// `<prefix>-00000.ppm`, `<prefix>-00001.ppm`, and so on.
+#[derive(Debug, Clone)]
+pub struct Frames {
+    directory: PathBuf,
+    prefix: String,
+    scale: usize,
+    format: Format,
+    next: usize,
+}
+
+impl Frames {
+    /// Create a frame writer, creating `directory` if needed
+    pub fn new<P: AsRef<Path>>(directory: P, prefix: &str) -> Result<Frames, Error> {
+        fs::create_dir_all(directory.as_ref())?;
+        Ok(Frames {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            scale: 1,
+            format: Format::Ppm,
+            next: 0,
+        })
+    }
+
+    pub fn scale(mut self, scale: usize) -> Frames {
+        self.scale = scale;
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Frames {
+        self.format = format;
+        self
+    }
+
+    /// The number of frames written so far
+    pub fn count(&self) -> usize {
+        self.next
+    }
+
+    /// Render `grid` and write it as the next frame, returning the path of the file
+    pub fn write<G, F>(&mut self, grid: &G, colour: F) -> Result<PathBuf, Error>
+    where
+        G: GridLike,
+        F: Fn(Position, Option<&G::Item>) -> Rgb,
+    {
+        let image = Image::render(grid, self.scale, colour);
+        let file_name = format!(
+            "{}-{:05}.{}",
+            self.prefix,
+            self.next,
+            self.format.extension()
+        );
+        let path = self.directory.join(file_name);
+        match self.format {
+            Format::Ppm => image.save_ppm(&path)?,
+            #[cfg(feature = "png")]
+            Format::Png => image.save_png(&path)?,
+        }
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::Matrix;
+
+    #[test]
+    fn test_render_ppm() -> Result<(), Error> {
+        let matrix: Matrix = "#./.#".parse()?;
+        let image = Image::render(&matrix, 2, |_, value| {
+            if value == Some(&true) {
+                Rgb::WHITE
+            } else {
+                Rgb::BLACK
+            }
+        });
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.get(1, 1), Rgb::WHITE);
+        assert_eq!(image.get(2, 1), Rgb::BLACK);
+        assert_eq!(image.get(3, 3), Rgb::WHITE);
+
+        let mut bytes = Vec::new();
+        image.write_ppm(&mut bytes)?;
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&bytes[..header.len()], header);
+        assert_eq!(bytes.len(), header.len() + 4 * 4 * 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_frames() -> Result<(), Error> {
+        let root = tempfile::TempDir::new()?;
+        let directory = root.path().join("frames");
+        let mut frames = Frames::new(&directory, "life")?.scale(3);
+        assert_eq!(frames.count(), 0);
+
+        let colour = |_, value: Option<&bool>| {
+            if value == Some(&true) {
+                Rgb::WHITE
+            } else {
+                Rgb::BLACK
+            }
+        };
+        let first = frames.write(&"#.".parse::<Matrix>()?, colour)?;
+        let second = frames.write(&"#./.#/##".parse::<Matrix>()?, colour)?;
+        assert_eq!(frames.count(), 2);
+        assert_eq!(first, directory.join("life-00000.ppm"));
+        assert_eq!(second, directory.join("life-00001.ppm"));
+
+        for (path, header) in &[(first, "P6\n6 3\n255\n"), (second, "P6\n6 9\n255\n")] {
+            let bytes = fs::read(path)?;
+            assert!(bytes.starts_with(header.as_bytes()));
+        }
+        let mut names = fs::read_dir(&directory)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+            .collect::<Result<Vec<_>, Error>>()?;
+        names.sort();
+        assert_eq!(names, vec!["life-00000.ppm", "life-00001.ppm"]);
+        Ok(())
+    }
+}
@@ -41,6 +41,48 @@ where
         })
         .collect::<Result<Vec<N>, _>>()
 }
+
+/// Extract inclusive ranges written as `low-high` from string.
+///
+/// Both ends may be negative, so `-5--3` is the range from -5 to -3.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::input::get_ranges;
+/// assert_eq!(get_ranges::<i32>("class: 1-3 or 5-7")?, vec![(1, 3), (5, 7)]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn get_ranges<N: Copy + Clone + Debug + FromStr>(input: &str) -> Result<Vec<(N, N)>, Error>
+where
+    <N as std::str::FromStr>::Err: StdError,
+{
+    lazy_static! {
+        static ref RANGE: Regex = Regex::new(r"(-?\d+)-(-?\d+)").unwrap();
+    }
+
+    let parse = |number: &str| match number.parse::<N>() {
+        Ok(number) => Ok(number),
+        Err(err) => {
+            let message = format!(
+                "Could not convert \"{}\" from \"{}\", reason is {:?}",
+                number,
+                input,
+                err.to_string()
+            );
+            Err(failure::err_msg(message))
+        }
+    };
+
+    RANGE
+        .captures_iter(input)
+        .map(|captures| Ok((parse(&captures[1])?, parse(&captures[2])?)))
+        .collect()
+}
+
 /// Extract numbers from string.
 ///
 /// # Examples
@@ -98,6 +140,19 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ranges() -> Result<(), Error> {
+        assert_eq!(get_ranges::<u32>("1-3")?, vec![(1, 3)]);
+        assert_eq!(
+            get_ranges::<i32>("departure track: 25-80 or 90-961, -4--2")?,
+            vec![(25, 80), (90, 961), (-4, -2)]
+        );
+        assert_eq!(get_ranges::<i32>("1 - 3, 7")?, vec![]);
+        assert!(get_ranges::<u8>("1-1000").is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_failure() {
         assert!(get_numbers::<u8>("1000").is_err());
@@ -0,0 +1,117 @@
+use crate::UnionFind;
+use hashbrown::HashMap;
+use std::iter::FromIterator;
+
+/// Maps names to dense ids in [0..len), in the order they are first interned
+///
+/// # Examples
+///
+/// ```
+/// use aoc2020::Interner;
+/// let mut interner = Interner::new();
+/// assert_eq!(interner.intern("shiny gold"), 0);
+/// assert_eq!(interner.intern("faded blue"), 1);
+/// assert_eq!(interner.intern("shiny gold"), 0);
+/// assert_eq!(interner.resolve(1), "faded blue");
+/// assert_eq!(interner.get("dark red"), None);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Interner {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// The id of `name`, giving it the next id if it is new
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    /// The id of `name`, if it has been interned
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).cloned()
+    }
+
+    /// The name with the given id
+    pub fn resolve(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    /// The number of names
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The ids and names in id order
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (id as u32, name.as_str()))
+    }
+
+    /// A UnionFind with one node for each name
+    pub fn union_find(&self) -> UnionFind {
+        UnionFind::new(self.len() as i32)
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Interner {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(names: I) -> Self {
+        let mut interner = Interner::new();
+        for name in names {
+            interner.intern(name);
+        }
+        interner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut interner = "byr iyr eyr hgt hcl ecl pid byr"
+            .split(' ')
+            .collect::<Interner>();
+        assert_eq!(interner.len(), 7);
+        assert_eq!(interner.get("pid"), Some(6));
+        assert_eq!(interner.intern("cid"), 7);
+        assert_eq!(interner.resolve(7), "cid");
+        assert_eq!(
+            interner.iter().map(|(_, name)| name).collect::<Vec<_>>(),
+            vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"]
+        );
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut interner = Interner::new();
+        let pairs = [("a", "b"), ("c", "d"), ("b", "e")];
+        for (a, b) in &pairs {
+            interner.intern(a);
+            interner.intern(b);
+        }
+        let mut union_find = interner.union_find();
+        for (a, b) in &pairs {
+            union_find.join(interner.intern(a) as i32, interner.intern(b) as i32);
+        }
+        assert_eq!(union_find.group_count(), 2);
+        assert_eq!(union_find.group_size(interner.intern("e") as i32), 3);
+    }
+}
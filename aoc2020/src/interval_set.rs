@@ -0,0 +1,271 @@
+use crate::input::get_ranges;
+use failure::{bail, Error};
+use num::PrimInt;
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+/// A set of integers stored as sorted, disjoint, inclusive intervals
+///
+/// Overlapping and adjacent intervals are merged, so `1-3` and `4-6` are stored as `1-6`.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::interval_set::IntervalSet;
+/// let class: IntervalSet<i32> = "class: 1-3 or 5-7".parse()?;
+/// assert!(class.contains(7));
+/// assert!(!class.contains(4));
+/// let row: IntervalSet<i32> = "row: 6-11 or 33-44".parse()?;
+/// assert_eq!(class.merge(&row).to_string(), "1-3, 5-11, 33-44");
+/// assert_eq!(class.intersect(&row).to_string(), "6-7");
+/// assert_eq!(row.subtract(&class).len(), 16);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct IntervalSet<T: PrimInt> {
+    intervals: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// The set of the numbers from `low` to `high`, empty if `low` is larger
+    pub fn interval(low: T, high: T) -> IntervalSet<T> {
+        let mut result = IntervalSet::new();
+        result.insert(low, high);
+        result
+    }
+
+    /// Build a set from possibly overlapping and unordered intervals
+    fn from_intervals(mut intervals: Vec<(T, T)>) -> IntervalSet<T> {
+        intervals.retain(|&(low, high)| low <= high);
+        intervals.sort();
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(intervals.len());
+        for (low, high) in intervals {
+            match merged.last_mut() {
+                Some(last) if last.1 == T::max_value() || low <= last.1 + T::one() => {
+                    last.1 = last.1.max(high);
+                }
+                _ => merged.push((low, high)),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    /// The intervals in increasing order
+    pub fn intervals(&self) -> &[(T, T)] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of numbers in the set
+    ///
+    /// The count is a `u128` since it may not fit `T`, such as the 256 numbers of `0-255`
+    /// for `u8`. Only the set of all 128 bit numbers is too large, and gives `u128::MAX`.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().fold(0u128, |sum, &(low, high)| {
+            let span = match (low.to_i128(), high.to_i128()) {
+                (Some(low), Some(high)) => high.wrapping_sub(low) as u128,
+                _ => {
+                    let low = low.to_u128().expect("Numbers are signed or unsigned");
+                    let high = high.to_u128().expect("Numbers are signed or unsigned");
+                    high - low
+                }
+            };
+            sum.saturating_add(span).saturating_add(1)
+        })
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.intervals
+            .binary_search_by(|&(low, high)| {
+                if high < value {
+                    Ordering::Less
+                } else if low > value {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// Add the numbers from `low` to `high`
+    pub fn insert(&mut self, low: T, high: T) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push((low, high));
+        *self = IntervalSet::from_intervals(intervals);
+    }
+
+    /// The numbers in either set
+    pub fn merge(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = self.intervals.clone();
+        intervals.extend_from_slice(&other.intervals);
+        IntervalSet::from_intervals(intervals)
+    }
+
+    /// The numbers in both sets
+    pub fn intersect(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (low1, high1) = self.intervals[i];
+            let (low2, high2) = other.intervals[j];
+            let (low, high) = (low1.max(low2), high1.min(high2));
+            if low <= high {
+                intervals.push((low, high));
+            }
+            if high1 < high2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The numbers in this set but not in `other`
+    pub fn subtract(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let mut removed = other.intervals.iter().peekable();
+        for &(low, high) in &self.intervals {
+            let mut start = Some(low);
+            while let (Some(from), Some(&&(remove_low, remove_high))) = (start, removed.peek()) {
+                if remove_high < from {
+                    removed.next();
+                    continue;
+                }
+                if remove_low > high {
+                    break;
+                }
+                if remove_low > from {
+                    intervals.push((from, remove_low - T::one()));
+                }
+                if remove_high >= high {
+                    start = None;
+                } else {
+                    start = Some(remove_high + T::one());
+                    removed.next();
+                }
+            }
+            if let Some(from) = start {
+                intervals.push((from, high));
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+impl<T: PrimInt + FromStr + Debug> FromStr for IntervalSet<T>
+where
+    <T as FromStr>::Err: std::error::Error,
+{
+    type Err = Error;
+
+    /// Parses all ranges like `1-3` in the text, so `class: 1-3 or 5-7` gives `1-3, 5-7`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ranges = get_ranges(s)?;
+        if let Some((low, high)) = ranges.iter().find(|(low, high)| low > high) {
+            bail!("The range {:?}-{:?} in \"{}\" is reversed", low, high, s);
+        }
+        Ok(IntervalSet::from_intervals(ranges))
+    }
+}
+
+/// The intervals separated by commas, like `1-3, 5-7`
+impl<T: PrimInt + fmt::Display> fmt::Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (low, high)) in self.intervals.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}-{}", low, high)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_edges() -> Result<(), Error> {
+        let mut set = IntervalSet::interval(250u8, 255);
+        set.insert(0, 3);
+        set.insert(4, 4);
+        set.insert(248, 249);
+        assert_eq!(set.intervals(), &[(0, 4), (248, 255)]);
+        assert_eq!(set.len(), 13);
+        assert!(set.contains(255) && set.contains(0) && !set.contains(5));
+        assert_eq!(
+            set.subtract(&IntervalSet::interval(2, 250)).to_string(),
+            "0-1, 251-255"
+        );
+        assert!(IntervalSet::interval(3, 2).is_empty());
+        assert_eq!(IntervalSet::interval(-100i8, 100).len(), 201);
+        assert_eq!(IntervalSet::interval(-128i8, 127).len(), 256);
+        assert_eq!(IntervalSet::interval(0u8, 255).len(), 256);
+        assert_eq!(IntervalSet::interval(u128::MAX - 1, u128::MAX).len(), 2);
+        assert_eq!(IntervalSet::interval(0, u128::MAX).len(), u128::MAX);
+        assert_eq!(IntervalSet::interval(i128::MIN, i128::MAX).len(), u128::MAX);
+        assert_eq!(IntervalSet::interval(i128::MIN, -1).len(), 1 << 127);
+
+        let negative: IntervalSet<i64> = "-10--5 or -3-2".parse()?;
+        assert_eq!(negative.intervals(), &[(-10, -5), (-3, 2)]);
+        assert!("5-3".parse::<IntervalSet<i32>>().is_err());
+        assert!("300-400".parse::<IntervalSet<u8>>().is_err());
+        Ok(())
+    }
+
+    fn intervals() -> impl Strategy<Value = Vec<(i32, i32)>> {
+        proptest::collection::vec((-40..40i32, 0..12i32), 0..6)
+            .prop_map(|v| v.into_iter().map(|(low, len)| (low, low + len)).collect())
+    }
+
+    fn naive(intervals: &[(i32, i32)]) -> BTreeSet<i32> {
+        intervals
+            .iter()
+            .flat_map(|&(low, high)| low..=high)
+            .collect()
+    }
+
+    fn values(set: &IntervalSet<i32>) -> BTreeSet<i32> {
+        naive(set.intervals())
+    }
+
+    proptest! {
+        #[test]
+        fn operations_match_naive_sets(a in intervals(), b in intervals()) {
+            let (set_a, set_b) = (IntervalSet::from_intervals(a.clone()), IntervalSet::from_intervals(b.clone()));
+            let (naive_a, naive_b) = (naive(&a), naive(&b));
+
+            prop_assert_eq!(values(&set_a), naive_a.clone());
+            prop_assert_eq!(set_a.len(), naive_a.len() as u128);
+            for pair in set_a.intervals().windows(2) {
+                prop_assert!(pair[0].1 + 1 < pair[1].0);
+            }
+            for value in -45..60 {
+                prop_assert_eq!(set_a.contains(value), naive_a.contains(&value));
+            }
+            prop_assert_eq!(values(&set_a.merge(&set_b)), &naive_a | &naive_b);
+            prop_assert_eq!(values(&set_a.intersect(&set_b)), &naive_a & &naive_b);
+            prop_assert_eq!(values(&set_a.subtract(&set_b)), &naive_a - &naive_b);
+            prop_assert_eq!(set_a.to_string().parse::<IntervalSet<i32>>().unwrap(), set_a);
+        }
+    }
+}
@@ -1,9 +1,30 @@
 pub mod union_find;
 pub use crate::union_find::UnionFind;
 
+pub mod interner;
+pub use crate::interner::Interner;
+
+pub mod assign;
+pub mod day_and_part;
 pub mod dense_grid;
+pub mod expr;
 pub mod geometry;
+pub mod grammar;
+pub mod graph;
+pub mod grid;
+pub mod image;
 pub mod input;
+pub mod interval_set;
 pub mod matrix;
+pub mod memo;
+pub mod ocr;
 pub mod position;
+pub mod ray;
+pub mod region;
+pub mod ring;
+pub mod samples;
+pub mod solver;
 pub mod sparse_grid;
+pub mod store;
+pub mod terminal;
+pub mod vm;
@@ -1,3 +1,7 @@
+use crate::dense_grid;
+use crate::grid::GridLike;
+use crate::position::{Position, Scalar};
+use crate::sparse_grid;
 use failure::Error;
 
 use std::fmt;
@@ -34,8 +38,8 @@ impl Matrix {
 
     pub fn slice(&self, start_x: usize, start_y: usize, width: usize, height: usize) -> Matrix {
         let mut data = Vec::with_capacity(width * height);
-        for x in start_x..(start_x + width) {
-            for y in start_y..(start_y + height) {
+        for y in start_y..(start_y + height) {
+            for x in start_x..(start_x + width) {
                 data.push(self[(x, y)])
             }
         }
@@ -89,7 +93,7 @@ impl Matrix {
     pub fn row(&self, y: usize) -> Matrix {
         let mut result = Matrix::new(self.width, 1);
         for x in 0..self.width {
-            result[(x, 1)] = self[(x, y)];
+            result[(x, 0)] = self[(x, y)];
         }
         result
     }
@@ -97,7 +101,7 @@ impl Matrix {
     pub fn col(&self, x: usize) -> Matrix {
         let mut result = Matrix::new(1, self.height);
         for y in 0..self.height {
-            result[(1, y)] = self[(x, y)];
+            result[(0, y)] = self[(x, y)];
         }
         result
     }
@@ -124,7 +128,7 @@ impl Matrix {
         for x in 0..self.width {
             for y in 0..self.height {
                 let xr90 = y;
-                let yr90 = self.height - x - 1;
+                let yr90 = self.width - x - 1;
                 result[(xr90, yr90)] = self[(x, y)];
             }
         }
@@ -149,6 +153,70 @@ impl Matrix {
     }
 }
 
+impl GridLike for Matrix {
+    type Item = bool;
+
+    fn get(&self, position: Position) -> Option<&bool> {
+        if self.in_bounds(position) {
+            Some(&self[(position.x as usize, position.y as usize)])
+        } else {
+            None
+        }
+    }
+
+    fn set(&mut self, position: Position, value: bool) {
+        assert!(
+            self.in_bounds(position),
+            "Position {} is not in bounds of {}x{} matrix",
+            position,
+            self.width,
+            self.height
+        );
+        self[(position.x as usize, position.y as usize)] = value;
+    }
+
+    fn bounds(&self) -> (Position, Position) {
+        (
+            Position::origo(),
+            Position::new(self.width as Scalar - 1, self.height as Scalar - 1),
+        )
+    }
+}
+
+/// The matrix covers the bounds of the grid, with the minimum corner at (0, 0)
+impl From<&dense_grid::Grid<bool>> for Matrix {
+    fn from(grid: &dense_grid::Grid<bool>) -> Self {
+        let mut result = Matrix::new(grid.width, grid.height);
+        for position in grid.positions() {
+            let x = (position.x - grid.min_x) as usize;
+            let y = (position.y - grid.min_y) as usize;
+            result[(x, y)] = grid[position];
+        }
+        result
+    }
+}
+
+/// The matrix covers the bounds of the grid, with the minimum corner at (0, 0).
+/// Positions without a value are false.
+impl From<&sparse_grid::Grid<bool>> for Matrix {
+    fn from(grid: &sparse_grid::Grid<bool>) -> Self {
+        let (min_pos, max_pos) = grid.bounds();
+        let mut result = if grid.is_empty() {
+            Matrix::new(0, 0)
+        } else {
+            Matrix::new(
+                (max_pos.x - min_pos.x + 1) as usize,
+                (max_pos.y - min_pos.y + 1) as usize,
+            )
+        };
+        for (position, &value) in grid.iter() {
+            let offset = position - min_pos;
+            result[(offset.x as usize, offset.y as usize)] = value;
+        }
+        result
+    }
+}
+
 impl<'a> IntoIterator for &'a Matrix {
     type Item = bool;
     type IntoIter = MatrixIterator<'a>;
@@ -200,16 +268,31 @@ impl IndexMut<(usize, usize)> for Matrix {
 impl FromStr for Matrix {
     type Err = Error;
 
+    /// Parses rows separated either by newlines, as written by `Display`, or by `/`.
+    /// All rows must have the same width.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
-        let rows: Vec<&str> = s.split('/').collect();
+        let rows: Vec<&str> = if s.contains('\n') {
+            s.lines().map(str::trim).collect()
+        } else {
+            s.split('/').collect()
+        };
         if rows.is_empty() {
             return Ok(Matrix::new(0, 0));
         }
-        let width = rows[0].len();
+        let width = rows[0].chars().count();
         let height = rows.len();
         let mut result = Matrix::new(width, height);
         for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(failure::format_err!(
+                    "Row {} of \"{}\" has width {}, expected {}",
+                    y,
+                    s,
+                    row.chars().count(),
+                    width
+                ));
+            }
             for (x, ch) in row.chars().enumerate() {
                 result[(x, y)] = ch == '#';
             }
@@ -229,3 +312,98 @@ impl fmt::Display for Matrix {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn matrix() -> impl Strategy<Value = Matrix> {
+        (0..8usize, 0..8usize).prop_flat_map(|(width, height)| {
+            proptest::collection::vec(any::<bool>(), width * height).prop_map(move |data| Matrix {
+                width,
+                height,
+                data,
+            })
+        })
+    }
+
+    #[test]
+    fn test_row_and_col() -> Result<(), Error> {
+        let matrix = "#../.#./..#".parse::<Matrix>()?;
+        assert_eq!(matrix.row(1), ".#.".parse()?);
+        assert_eq!(matrix.col(2), "././#".parse()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        assert!("#../.#".parse::<Matrix>().is_err());
+        assert!("#./.#.".parse::<Matrix>().is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn rot90_four_times_is_identity(matrix in matrix()) {
+            let rotated = matrix.rot90();
+            prop_assert_eq!((rotated.width, rotated.height), (matrix.height, matrix.width));
+            prop_assert_eq!(rotated.count_true(), matrix.count_true());
+            prop_assert_eq!(rotated.rot90().rot90().rot90(), matrix);
+        }
+
+        #[test]
+        fn flip_twice_is_identity(matrix in matrix()) {
+            prop_assert_eq!(matrix.flip().flip(), matrix);
+        }
+
+        #[test]
+        fn display_round_trips(matrix in matrix().prop_filter("non-empty", |m| m.width > 0 && m.height > 0)) {
+            prop_assert_eq!(matrix.to_string().parse::<Matrix>().unwrap(), matrix);
+        }
+
+        #[test]
+        fn rows_and_cols_match_indexing(matrix in matrix()) {
+            for y in 0..matrix.height {
+                let row = matrix.row(y);
+                for x in 0..matrix.width {
+                    prop_assert_eq!(row[(x, 0)], matrix[(x, y)]);
+                }
+            }
+            for x in 0..matrix.width {
+                let col = matrix.col(x);
+                for y in 0..matrix.height {
+                    prop_assert_eq!(col[(0, y)], matrix[(x, y)]);
+                }
+            }
+        }
+
+        #[test]
+        fn full_rotations_of_rows_and_cols_are_identity(matrix in matrix()) {
+            let mut rotated = matrix.clone();
+            for y in 0..matrix.height {
+                rotated.rotate_row(y, matrix.width);
+            }
+            for x in 0..matrix.width {
+                rotated.rotate_col(x, matrix.height);
+            }
+            prop_assert_eq!(rotated, matrix);
+        }
+
+        #[test]
+        fn slice_matches_indexing(
+            (matrix, start_x, start_y, width, height) in matrix().prop_flat_map(|matrix| {
+                let (w, h) = (matrix.width, matrix.height);
+                (Just(matrix), 0..=w, 0..=h).prop_flat_map(move |(matrix, x, y)| {
+                    (Just(matrix), Just(x), Just(y), 0..=w - x, 0..=h - y)
+                })
+            })
+        ) {
+            let slice = matrix.slice(start_x, start_y, width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    prop_assert_eq!(slice[(x, y)], matrix[(start_x + x, start_y + y)]);
+                }
+            }
+        }
+    }
+}
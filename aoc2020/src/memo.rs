@@ -0,0 +1,324 @@
+use failure::{bail, Error};
+use hashbrown::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// How often a `Memo` found a value in its cache
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Stats {
+    /// Values found in the cache
+    pub hits: usize,
+    /// Values computed by the function
+    pub misses: usize,
+    /// The number of times the cache was full and was cleared
+    pub clears: usize,
+}
+
+/// A cache for a recursive function, with detection of keys that depend on themselves
+///
+/// The function gets the key and a callback that gives the value of other keys, going
+/// through the cache. A key that is asked for again while its value is being computed is
+/// an error naming the keys involved, instead of infinite recursion.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::memo::Memo;
+/// let adapters = [1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19];
+/// let mut memo = Memo::new();
+/// let arrangements = memo.get(0, &|&joltage: &u32, arrangements| {
+///     if joltage == 19 {
+///         return Ok(1u64);
+///     }
+///     let mut total = 0;
+///     for next in joltage + 1..=joltage + 3 {
+///         if adapters.contains(&next) {
+///             total += arrangements(next)?;
+///         }
+///     }
+///     Ok(total)
+/// })?;
+/// assert_eq!(arrangements, 8);
+/// assert_eq!(memo.stats().misses, 12);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K: Hash + Eq + Clone + Debug, V: Clone> {
+    cache: HashMap<K, V>,
+    /// The keys being computed, outermost first
+    stack: Vec<K>,
+    active: HashSet<K>,
+    limit: Option<usize>,
+    stats: Stats,
+}
+
+impl<K: Hash + Eq + Clone + Debug, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone + Debug, V: Clone> Memo<K, V> {
+    /// A memo without a limit on the number of cached values
+    pub fn new() -> Memo<K, V> {
+        Memo {
+            cache: HashMap::new(),
+            stack: Vec::new(),
+            active: HashSet::new(),
+            limit: None,
+            stats: Stats::default(),
+        }
+    }
+
+    /// A memo that clears its cache when it would hold more than `limit` values
+    pub fn with_limit(limit: usize) -> Memo<K, V> {
+        Memo {
+            limit: Some(limit),
+            ..Memo::new()
+        }
+    }
+
+    /// The number of cached values
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Forget all cached values, keeping the statistics
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// The cached value for key, if there is one
+    pub fn cached(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// The value of `function` for key, computed at most once while it stays cached
+    pub fn get<F>(&mut self, key: K, function: &F) -> Result<V, Error>
+    where
+        F: Fn(&K, &mut dyn FnMut(K) -> Result<V, Error>) -> Result<V, Error>,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return Ok(value.clone());
+        }
+        if self.active.contains(&key) {
+            let start = self
+                .stack
+                .iter()
+                .position(|active| *active == key)
+                .expect("Active keys are on the stack");
+            let cycle = self.stack[start..]
+                .iter()
+                .chain(Some(&key))
+                .map(|key| format!("{:?}", key))
+                .collect::<Vec<_>>();
+            bail!("Recursion cycle {}", cycle.join(" -> "));
+        }
+
+        self.stats.misses += 1;
+        self.active.insert(key.clone());
+        self.stack.push(key.clone());
+        let result = function(&key, &mut |other| self.get(other, function));
+        self.stack.pop();
+        self.active.remove(&key);
+
+        let value = result?;
+        if self.limit.is_some_and(|limit| self.cache.len() >= limit) {
+            self.cache.clear();
+            self.stats.clears += 1;
+        }
+        if self.limit != Some(0) {
+            self.cache.insert(key, value.clone());
+        }
+        Ok(value)
+    }
+}
+
+/// The value of a recursive function for key, with a cache that is dropped afterwards
+pub fn memoise<K, V, F>(key: K, function: &F) -> Result<V, Error>
+where
+    K: Hash + Eq + Clone + Debug,
+    V: Clone,
+    F: Fn(&K, &mut dyn FnMut(K) -> Result<V, Error>) -> Result<V, Error>,
+{
+    Memo::new().get(key, function)
+}
+
+/// A 64 bit FNV-1a hasher, which unlike the standard hashers gives the same hash for the
+/// same value on every run
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Fingerprinter(u64);
+
+impl Default for Fingerprinter {
+    fn default() -> Self {
+        Fingerprinter(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fingerprinter {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// A hash of value that is the same on every run, for storing seen states compactly
+///
+/// Different values may get the same fingerprint, although that is unlikely for the few
+/// million states of a puzzle.
+pub fn fingerprint<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = Fingerprinter::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    type Decks = (Vec<u8>, Vec<u8>);
+
+    fn score(deck: &VecDeque<u8>) -> u64 {
+        deck.iter()
+            .rev()
+            .enumerate()
+            .map(|(index, &card)| (index as u64 + 1) * u64::from(card))
+            .sum()
+    }
+
+    /// The winner and their score in a game of Recursive Combat
+    fn combat(
+        decks: &Decks,
+        game: &mut dyn FnMut(Decks) -> Result<(u8, u64), Error>,
+    ) -> Result<(u8, u64), Error> {
+        let mut first = decks.0.iter().cloned().collect::<VecDeque<_>>();
+        let mut second = decks.1.iter().cloned().collect::<VecDeque<_>>();
+        let mut seen = HashSet::new();
+        while let (Some(&a), Some(&b)) = (first.front(), second.front()) {
+            if !seen.insert(fingerprint(&(&first, &second))) {
+                return Ok((1, score(&first)));
+            }
+            first.pop_front();
+            second.pop_front();
+            let winner = if first.len() >= a as usize && second.len() >= b as usize {
+                let sub_game = (
+                    first.iter().take(a as usize).cloned().collect(),
+                    second.iter().take(b as usize).cloned().collect(),
+                );
+                game(sub_game)?.0
+            } else if a > b {
+                1
+            } else {
+                2
+            };
+            if winner == 1 {
+                first.extend(&[a, b]);
+            } else {
+                second.extend(&[b, a]);
+            }
+        }
+        if first.is_empty() {
+            Ok((2, score(&second)))
+        } else {
+            Ok((1, score(&first)))
+        }
+    }
+
+    #[test]
+    fn test_recursive_combat() -> Result<(), Error> {
+        let decks = (vec![9, 2, 6, 3, 1], vec![5, 8, 4, 7, 10]);
+        assert_eq!(memoise(decks, &combat)?, (2, 291));
+
+        let looping = (vec![43, 19], vec![2, 29, 14]);
+        assert_eq!(memoise(looping, &combat)?.0, 1);
+        Ok(())
+    }
+
+    fn fibonacci(
+        &n: &u64,
+        fibonacci: &mut dyn FnMut(u64) -> Result<u64, Error>,
+    ) -> Result<u64, Error> {
+        if n < 2 {
+            Ok(n)
+        } else {
+            Ok(fibonacci(n - 1)? + fibonacci(n - 2)?)
+        }
+    }
+
+    #[test]
+    fn test_stats_and_limit() -> Result<(), Error> {
+        let mut memo = Memo::new();
+        assert_eq!(memo.get(90, &fibonacci)?, 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 88,
+                misses: 91,
+                clears: 0
+            }
+        );
+        assert_eq!(memo.get(50, &fibonacci)?, 12_586_269_025);
+        assert_eq!(memo.stats().hits, 89);
+        assert_eq!(memo.cached(&10), Some(&55));
+
+        let mut memo = Memo::with_limit(3);
+        assert_eq!(memo.get(25, &fibonacci)?, 75_025);
+        assert!(memo.len() <= 3);
+        assert!(memo.stats().clears > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle() -> Result<(), Error> {
+        let mut memo = Memo::new();
+        let error = memo
+            .get(0, &|&n: &u32, next| {
+                if n == 5 {
+                    Ok(0)
+                } else {
+                    next((n + 1) % 3)
+                }
+            })
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Recursion cycle 0 -> 1 -> 2 -> 0");
+        assert!(memo.is_empty());
+        assert_eq!(memo.get(5, &|_, _| Ok(1))?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            fingerprint(&(vec![1u8, 2], vec![3u8])),
+            fingerprint(&(vec![1u8, 2], vec![3u8]))
+        );
+        assert_ne!(
+            fingerprint(&(vec![1u8, 2], vec![3u8])),
+            fingerprint(&(vec![1u8], vec![2u8, 3]))
+        );
+        assert_eq!(
+            fingerprint(""),
+            (0xcbf2_9ce4_8422_2325u64 ^ 0xff).wrapping_mul(0x0100_0000_01b3)
+        );
+    }
+}
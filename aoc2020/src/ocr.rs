@@ -0,0 +1,290 @@
+use crate::matrix::Matrix;
+use failure::{bail, Error};
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use std::fmt::Write;
+
+/// The 4 by 6 letters, with rows separated by `/`
+const SMALL_GLYPHS: &[(char, &str)] = &[
+    ('A', ".##./#..#/#..#/####/#..#/#..#"),
+    ('B', "###./#..#/###./#..#/#..#/###."),
+    ('C', ".##./#..#/#.../#.../#..#/.##."),
+    ('E', "####/#.../###./#.../#.../####"),
+    ('F', "####/#.../###./#.../#.../#..."),
+    ('G', ".##./#..#/#.../#.##/#..#/.###"),
+    ('H', "#..#/#..#/####/#..#/#..#/#..#"),
+    ('I', ".###/..#./..#./..#./..#./.###"),
+    ('J', "..##/...#/...#/...#/#..#/.##."),
+    ('K', "#..#/#.#./##../#.#./#.#./#..#"),
+    ('L', "#.../#.../#.../#.../#.../####"),
+    ('O', ".##./#..#/#..#/#..#/#..#/.##."),
+    ('P', "###./#..#/#..#/###./#.../#..."),
+    ('R', "###./#..#/#..#/###./#.#./#..#"),
+    ('S', ".###/#.../#.../.##./...#/###."),
+    ('U', "#..#/#..#/#..#/#..#/#..#/.##."),
+    ('Z', "####/...#/..#./.#../#.../####"),
+];
+
+/// The 6 by 10 letters, with rows separated by `/`
+const LARGE_GLYPHS: &[(char, &str)] = &[
+    (
+        'A',
+        "..##../.#..#./#....#/#....#/#....#/######/#....#/#....#/#....#/#....#",
+    ),
+    (
+        'B',
+        "#####./#....#/#....#/#....#/#####./#....#/#....#/#....#/#....#/#####.",
+    ),
+    (
+        'C',
+        ".####./#....#/#...../#...../#...../#...../#...../#...../#....#/.####.",
+    ),
+    (
+        'E',
+        "######/#...../#...../#...../#####./#...../#...../#...../#...../######",
+    ),
+    (
+        'F',
+        "######/#...../#...../#...../#####./#...../#...../#...../#...../#.....",
+    ),
+    (
+        'G',
+        ".####./#....#/#...../#...../#...../#..###/#....#/#....#/#...##/.###.#",
+    ),
+    (
+        'H',
+        "#....#/#....#/#....#/#....#/######/#....#/#....#/#....#/#....#/#....#",
+    ),
+    (
+        'J',
+        "...###/....#./....#./....#./....#./....#./....#./#...#./#...#./.###..",
+    ),
+    (
+        'K',
+        "#....#/#...#./#..#../#.#.../##..../##..../#.#.../#..#../#...#./#....#",
+    ),
+    (
+        'L',
+        "#...../#...../#...../#...../#...../#...../#...../#...../#...../######",
+    ),
+    (
+        'N',
+        "#....#/##...#/##...#/#.#..#/#.#..#/#..#.#/#..#.#/#...##/#...##/#....#",
+    ),
+    (
+        'P',
+        "#####./#....#/#....#/#....#/#####./#...../#...../#...../#...../#.....",
+    ),
+    (
+        'R',
+        "#####./#....#/#....#/#....#/#####./#..#../#...#./#...#./#....#/#....#",
+    ),
+    (
+        'X',
+        "#....#/#....#/.#..#./.#..#./..##../..##../.#..#./.#..#./#....#/#....#",
+    ),
+    (
+        'Z',
+        "######/.....#/.....#/....#./...#../..#.../.#..../#...../#...../######",
+    ),
+];
+
+/// The block letter fonts used in puzzle answers
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Font {
+    /// Letters 4 pixels wide and 6 high, one column apart
+    Small,
+    /// Letters 6 pixels wide and 10 high, two columns apart
+    Large,
+}
+
+impl Font {
+    /// The font with letters of the given height
+    pub fn for_height(height: usize) -> Option<Font> {
+        match height {
+            6 => Some(Font::Small),
+            10 => Some(Font::Large),
+            _ => None,
+        }
+    }
+
+    /// The width and height of a letter
+    pub fn size(self) -> (usize, usize) {
+        match self {
+            Font::Small => (4, 6),
+            Font::Large => (6, 10),
+        }
+    }
+
+    /// The distance between the start of two letters
+    pub fn stride(self) -> usize {
+        match self {
+            Font::Small => 5,
+            Font::Large => 8,
+        }
+    }
+
+    fn glyphs(self) -> &'static HashMap<Matrix, char> {
+        lazy_static! {
+            static ref SMALL: HashMap<Matrix, char> = parse_glyphs(SMALL_GLYPHS);
+            static ref LARGE: HashMap<Matrix, char> = parse_glyphs(LARGE_GLYPHS);
+        }
+        match self {
+            Font::Small => &SMALL,
+            Font::Large => &LARGE,
+        }
+    }
+}
+
+fn parse_glyphs(glyphs: &[(char, &str)]) -> HashMap<Matrix, char> {
+    glyphs
+        .iter()
+        .map(|&(letter, pixels)| {
+            let glyph = pixels.parse().expect("Glyphs are valid matrices");
+            (glyph, letter)
+        })
+        .collect()
+}
+
+/// The cell for letter `index`, with pixels outside the matrix unset
+fn cell(matrix: &Matrix, font: Font, index: usize) -> Matrix {
+    let (width, height) = font.size();
+    let start = index * font.stride();
+    let mut result = Matrix::new(width, height);
+    for y in 0..height.min(matrix.height) {
+        for x in 0..width {
+            if start + x < matrix.width {
+                result[(x, y)] = matrix[(start + x, y)];
+            }
+        }
+    }
+    result
+}
+
+/// Read the block letters in a matrix, choosing the font from the height of the matrix
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::matrix::Matrix;
+/// use aoc2020::ocr::recognise;
+/// let matrix: Matrix = "#..#..###/#..#...#./####...#./#..#...#./#..#...#./#..#..###".parse()?;
+/// assert_eq!(recognise(&matrix)?, "HI");
+/// # Ok(())
+/// # }
+/// ```
+pub fn recognise(matrix: &Matrix) -> Result<String, Error> {
+    match Font::for_height(matrix.height) {
+        Some(font) => recognise_with(matrix, font),
+        None => bail!(
+            "No font has letters {} pixels high, use 6 or 10",
+            matrix.height
+        ),
+    }
+}
+
+/// Read the block letters in a matrix with the given font
+///
+/// The letters start in the top left corner. Cells without any pixels set, such as the
+/// padding after the last letter, are skipped. Unrecognised cells are an error showing
+/// each of them.
+pub fn recognise_with(matrix: &Matrix, font: Font) -> Result<String, Error> {
+    let count = matrix.width.div_ceil(font.stride());
+    let mut result = String::new();
+    let mut unrecognised = Vec::new();
+    for index in 0..count {
+        let cell = cell(matrix, font, index);
+        if cell.count_true() == 0 {
+            continue;
+        }
+        match font.glyphs().get(&cell) {
+            Some(&letter) => result.push(letter),
+            None => unrecognised.push((index, cell)),
+        }
+    }
+
+    if unrecognised.is_empty() {
+        Ok(result)
+    } else {
+        let mut message = format!(
+            "Could not recognise {} of the letters, read \"{}\"",
+            unrecognised.len(),
+            result
+        );
+        for (index, cell) in unrecognised {
+            write!(
+                message,
+                "\nLetter {}:\n{}",
+                index,
+                cell.to_string().trim_end()
+            )?;
+        }
+        bail!(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str, font: Font) -> Matrix {
+        let (width, height) = font.size();
+        let glyphs = font
+            .glyphs()
+            .iter()
+            .map(|(glyph, &letter)| (letter, glyph))
+            .collect::<HashMap<_, _>>();
+        let mut result = Matrix::new(text.len() * font.stride(), height);
+        for (index, letter) in text.chars().enumerate() {
+            let glyph = glyphs[&letter];
+            for y in 0..height {
+                for x in 0..width {
+                    result[(index * font.stride() + x, y)] = glyph[(x, y)];
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_all_letters() -> Result<(), Error> {
+        for &(font, glyphs) in &[(Font::Small, SMALL_GLYPHS), (Font::Large, LARGE_GLYPHS)] {
+            let text = glyphs.iter().map(|&(letter, _)| letter).collect::<String>();
+            assert_eq!(font.glyphs().len(), text.len());
+            assert_eq!(recognise(&render(&text, font))?, text);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_output() -> Result<(), Error> {
+        // Letters without the trailing padding column
+        let matrix: Matrix = "\
+            ######..#####.\n\
+            #.......#....#\n\
+            #.......#....#\n\
+            #.......#....#\n\
+            #####...#####.\n\
+            #.......#.....\n\
+            #.......#.....\n\
+            #.......#.....\n\
+            #.......#.....\n\
+            ######..#.....\n"
+            .parse()?;
+        assert_eq!(recognise(&matrix)?, "EP");
+        Ok(())
+    }
+
+    #[test]
+    fn test_unrecognised() -> Result<(), Error> {
+        let mut matrix = render("ABC", Font::Small);
+        matrix[(8, 0)] = true;
+        let error = recognise(&matrix).unwrap_err().to_string();
+        assert!(error.starts_with("Could not recognise 1 of the letters, read \"AC\""));
+        assert!(error.contains("Letter 1:\n####\n#..#\n###.\n#..#\n#..#\n###."));
+        assert!(recognise(&Matrix::new(5, 7)).is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,13 @@
+use std::cmp::max;
+use std::cmp::Ordering;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
 use strum_macros::EnumString;
 
 pub trait Step<T: Copy>
@@ -13,7 +20,11 @@ where
 
 pub type Scalar = i32;
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+/// A position (or offset) on an integer grid.
+///
+/// The y-axis points downwards, so `North` is towards smaller y-values.
+/// Positions are ordered in reading order, that is first by y and then by x.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Position {
     pub x: Scalar,
     pub y: Scalar,
@@ -23,6 +34,75 @@ impl Position {
     pub fn new(x: Scalar, y: Scalar) -> Position {
         Position { x, y }
     }
+
+    pub fn origo() -> Position {
+        Position { x: 0, y: 0 }
+    }
+
+    /// The Manhattan (taxicab) distance between two positions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc2020::position::Position;
+    /// assert_eq!(Position::new(1, 2).manhattan_distance(Position::new(-2, 4)), 5);
+    /// ```
+    pub fn manhattan_distance(self, other: Position) -> Scalar {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The Chebyshev (chessboard) distance between two positions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc2020::position::Position;
+    /// assert_eq!(Position::new(1, 2).chebyshev_distance(Position::new(-2, 4)), 3);
+    /// ```
+    pub fn chebyshev_distance(self, other: Position) -> Scalar {
+        max((self.x - other.x).abs(), (self.y - other.y).abs())
+    }
+
+    /// The Manhattan distance to origo
+    pub fn manhattan_length(self) -> Scalar {
+        self.manhattan_distance(Position::origo())
+    }
+
+    /// Rotate this position 90 degrees around origo
+    ///
+    /// Turning `North` to the `Right` gives `East`, in the same way as for `Cardinal::turn`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc2020::position::{Cardinal, Position, Turn};
+    /// let north: Position = Cardinal::North.into();
+    /// assert_eq!(north.rotate(Turn::Right), Cardinal::East.into());
+    /// assert_eq!(Position::new(10, 4).rotate(Turn::Left), Position::new(4, -10));
+    /// ```
+    pub fn rotate(self, turn: Turn) -> Position {
+        match turn {
+            Turn::Left => Position::new(self.y, -self.x),
+            Turn::Right => Position::new(-self.y, self.x),
+        }
+    }
+
+    /// Rotate this position 90 degrees around `center`
+    pub fn rotate_around(self, center: Position, turn: Turn) -> Position {
+        (self - center).rotate(turn) + center
+    }
+}
+
+impl Ord for Position {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.y.cmp(&other.y).then(self.x.cmp(&other.x))
+    }
+}
+
+impl PartialOrd for Position {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Display for Position {
@@ -79,6 +159,27 @@ impl Step<Direction> for Position {
     }
 }
 
+impl Step<Compass> for Position {
+    fn step(&self, direction: Compass) -> Self {
+        self + Position::from(direction)
+    }
+
+    fn step_by(&self, direction: Compass, steps: Scalar) -> Self {
+        self + Position::from(direction) * steps
+    }
+}
+
+/// Stepping with a position steps by it as an offset
+impl Step<Position> for Position {
+    fn step(&self, direction: Position) -> Self {
+        self + direction
+    }
+
+    fn step_by(&self, direction: Position, steps: Scalar) -> Self {
+        self + direction * steps
+    }
+}
+
 impl From<(Scalar, Scalar)> for Position {
     fn from(pos: (Scalar, Scalar)) -> Self {
         Position { x: pos.0, y: pos.1 }
@@ -141,6 +242,98 @@ impl Add<&Position> for Position {
     }
 }
 
+impl Sub for Position {
+    type Output = Position;
+
+    fn sub(self, rhs: Position) -> Position {
+        (self.x - rhs.x, self.y - rhs.y).into()
+    }
+}
+
+impl Sub for &Position {
+    type Output = Position;
+
+    fn sub(self, rhs: &Position) -> Position {
+        (self.x - rhs.x, self.y - rhs.y).into()
+    }
+}
+
+impl Sub<Position> for &Position {
+    type Output = Position;
+
+    fn sub(self, rhs: Position) -> Position {
+        (self.x - rhs.x, self.y - rhs.y).into()
+    }
+}
+
+impl Sub<&Position> for Position {
+    type Output = Position;
+
+    fn sub(self, rhs: &Position) -> Position {
+        (self.x - rhs.x, self.y - rhs.y).into()
+    }
+}
+
+impl Neg for Position {
+    type Output = Position;
+
+    fn neg(self) -> Position {
+        (-self.x, -self.y).into()
+    }
+}
+
+impl Neg for &Position {
+    type Output = Position;
+
+    fn neg(self) -> Position {
+        (-self.x, -self.y).into()
+    }
+}
+
+impl Mul<Scalar> for Position {
+    type Output = Position;
+
+    fn mul(self, rhs: Scalar) -> Position {
+        (self.x * rhs, self.y * rhs).into()
+    }
+}
+
+impl Mul<Scalar> for &Position {
+    type Output = Position;
+
+    fn mul(self, rhs: Scalar) -> Position {
+        (self.x * rhs, self.y * rhs).into()
+    }
+}
+
+impl AddAssign for Position {
+    fn add_assign(&mut self, rhs: Position) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl AddAssign<&Position> for Position {
+    fn add_assign(&mut self, rhs: &Position) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl SubAssign for Position {
+    fn sub_assign(&mut self, rhs: Position) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl SubAssign<&Position> for Position {
+    fn sub_assign(&mut self, rhs: &Position) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
 #[derive(EnumString, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Turn {
     Left,
@@ -222,6 +415,125 @@ impl From<Direction> for Cardinal {
     }
 }
 
+impl From<Cardinal> for Position {
+    fn from(cardinal: Cardinal) -> Self {
+        Position::origo().step(cardinal)
+    }
+}
+
+impl From<Direction> for Position {
+    fn from(direction: Direction) -> Self {
+        Position::origo().step(direction)
+    }
+}
+
+/// The eight compass directions, including the diagonals.
+///
+/// Can be parsed both from the full name (`NorthEast`) and from the abbreviation (`NE`).
+///
+/// # Examples
+///
+/// ```
+/// use aoc2020::position::{Compass, Turn};
+/// assert_eq!("NE".parse::<Compass>(), Ok(Compass::NorthEast));
+/// assert_eq!(Compass::North.turn(Turn::Left), Compass::NorthWest);
+/// assert_eq!(Compass::West.turn_by(Turn::Left, 3), Compass::SouthEast);
+/// ```
+#[derive(EnumString, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Compass {
+    #[strum(serialize = "North", serialize = "N")]
+    North,
+    #[strum(serialize = "NorthEast", serialize = "NE")]
+    NorthEast,
+    #[strum(serialize = "East", serialize = "E")]
+    East,
+    #[strum(serialize = "SouthEast", serialize = "SE")]
+    SouthEast,
+    #[strum(serialize = "South", serialize = "S")]
+    South,
+    #[strum(serialize = "SouthWest", serialize = "SW")]
+    SouthWest,
+    #[strum(serialize = "West", serialize = "W")]
+    West,
+    #[strum(serialize = "NorthWest", serialize = "NW")]
+    NorthWest,
+}
+
+impl Compass {
+    /// All directions in clockwise order starting from `North`, same order as `connect8`
+    pub const ALL: [Compass; 8] = [
+        Compass::North,
+        Compass::NorthEast,
+        Compass::East,
+        Compass::SouthEast,
+        Compass::South,
+        Compass::SouthWest,
+        Compass::West,
+        Compass::NorthWest,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Turn 45 degrees
+    pub fn turn(self, turn: Turn) -> Compass {
+        self.turn_by(turn, 1)
+    }
+
+    /// Turn `steps` times 45 degrees, so two steps is a 90 degree turn
+    pub fn turn_by(self, turn: Turn, steps: usize) -> Compass {
+        let steps = steps % 8;
+        let index = match turn {
+            Turn::Left => self.index() + 8 - steps,
+            Turn::Right => self.index() + steps,
+        };
+        Compass::ALL[index % 8]
+    }
+
+    pub fn opposite(self) -> Compass {
+        self.turn_by(Turn::Right, 4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+}
+
+impl From<Cardinal> for Compass {
+    fn from(cardinal: Cardinal) -> Self {
+        match cardinal {
+            Cardinal::North => Compass::North,
+            Cardinal::South => Compass::South,
+            Cardinal::East => Compass::East,
+            Cardinal::West => Compass::West,
+        }
+    }
+}
+
+impl From<Direction> for Compass {
+    fn from(direction: Direction) -> Self {
+        Cardinal::from(direction).into()
+    }
+}
+
+impl From<Compass> for Position {
+    fn from(compass: Compass) -> Self {
+        use crate::position::Compass::*;
+        let (x, y) = match compass {
+            North => (0, -1),
+            NorthEast => (1, -1),
+            East => (1, 0),
+            SouthEast => (1, 1),
+            South => (0, 1),
+            SouthWest => (-1, 1),
+            West => (-1, 0),
+            NorthWest => (-1, -1),
+        };
+        Position { x, y }
+    }
+}
+
 pub fn connect<P>(position: P) -> impl Iterator<Item = Position>
 where
     P: Into<Position>,
@@ -253,3 +565,93 @@ where
     let position: Position = position.into();
     OFFSETS.iter().map(move |&offset| position + offset)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn position() -> impl Strategy<Value = Position> {
+        (-10_000..10_000, -10_000..10_000).prop_map(|(x, y)| Position::new(x, y))
+    }
+
+    fn turn() -> impl Strategy<Value = Turn> {
+        prop_oneof![Just(Turn::Left), Just(Turn::Right)]
+    }
+
+    #[test]
+    fn test_compass() {
+        assert_eq!(Compass::North.turn_by(Turn::Right, 2), Compass::East);
+        assert_eq!(Compass::North.turn_by(Turn::Left, 10), Compass::West);
+        assert_eq!(
+            Compass::SouthWest.turn_by(Turn::Right, 8),
+            Compass::SouthWest
+        );
+        assert_eq!(Compass::NorthEast.opposite(), Compass::SouthWest);
+        assert!(Compass::NorthWest.is_diagonal());
+        assert!(!Compass::South.is_diagonal());
+
+        let offsets = Compass::ALL
+            .iter()
+            .map(|&compass| Position::from(compass))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, connect8(Position::origo()).collect::<Vec<_>>());
+        for &compass in &Compass::ALL {
+            let offset = Position::from(compass);
+            assert_eq!(compass.opposite().opposite(), compass);
+            assert_eq!(Position::from(compass.opposite()), -offset);
+            assert_eq!(compass.is_diagonal(), offset.x != 0 && offset.y != 0);
+            assert_eq!(compass.turn(Turn::Right).turn(Turn::Left), compass);
+            assert_eq!(Position::origo().step_by(compass, 3), offset * 3);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn add_and_sub_are_inverses(a in position(), b in position()) {
+            prop_assert_eq!(a + b - b, a);
+            prop_assert_eq!(a - b, a + -b);
+            prop_assert_eq!(-(-a), a);
+            let mut c = a;
+            c += b;
+            c -= a;
+            prop_assert_eq!(c, b);
+        }
+
+        #[test]
+        fn scaling_scales_length(a in position(), k in -100..100) {
+            prop_assert_eq!((a * k).manhattan_length(), k.abs() * a.manhattan_length());
+            prop_assert_eq!(a * 2, a + a);
+        }
+
+        #[test]
+        fn distances_are_metrics(a in position(), b in position(), c in position()) {
+            prop_assert_eq!(a.manhattan_distance(b), b.manhattan_distance(a));
+            prop_assert_eq!(a.manhattan_distance(b), (a - b).manhattan_length());
+            prop_assert!(a.manhattan_distance(c) <= a.manhattan_distance(b) + b.manhattan_distance(c));
+            prop_assert!(a.chebyshev_distance(c) <= a.chebyshev_distance(b) + b.chebyshev_distance(c));
+            prop_assert!(a.chebyshev_distance(b) <= a.manhattan_distance(b));
+            prop_assert!(a.manhattan_distance(b) <= 2 * a.chebyshev_distance(b));
+        }
+
+        #[test]
+        fn rotations_cancel(a in position(), center in position(), turn in turn()) {
+            let other = match turn {
+                Turn::Left => Turn::Right,
+                Turn::Right => Turn::Left,
+            };
+            prop_assert_eq!(a.rotate(turn).rotate(other), a);
+            prop_assert_eq!(a.rotate(turn).rotate(turn).rotate(turn).rotate(turn), a);
+            prop_assert_eq!(a.rotate(turn).manhattan_length(), a.manhattan_length());
+            prop_assert_eq!(
+                a.rotate_around(center, turn).manhattan_distance(center),
+                a.manhattan_distance(center)
+            );
+        }
+
+        #[test]
+        fn ordering_is_reading_order(a in position(), b in position()) {
+            prop_assert_eq!(a.cmp(&b), (a.y, a.x).cmp(&(b.y, b.x)));
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::position::*;
+
+/// Iterator over the positions seen when walking from a position in a direction.
+///
+/// The start position itself is not included, and the iteration stops when the walk
+/// leaves the (inclusive) bounds given by `min` and `max`. A direction that does not
+/// move, such as the offset `(0, 0)`, gives no positions.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Ray<D> {
+    position: Position,
+    direction: D,
+    min: Position,
+    max: Position,
+    done: bool,
+}
+
+impl<D> Ray<D>
+where
+    D: Copy,
+    Position: Step<D>,
+{
+    pub fn new(from: Position, direction: D, min: Position, max: Position) -> Ray<D> {
+        Ray {
+            position: from,
+            direction,
+            min,
+            max,
+            done: false,
+        }
+    }
+
+    fn in_bounds(&self, position: Position) -> bool {
+        self.min.x <= position.x
+            && position.x <= self.max.x
+            && self.min.y <= position.y
+            && position.y <= self.max.y
+    }
+}
+
+impl<D> Iterator for Ray<D>
+where
+    D: Copy,
+    Position: Step<D>,
+{
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        if self.done {
+            return None;
+        }
+        let next = self.position.step(self.direction);
+        if next != self.position && self.in_bounds(next) {
+            self.position = next;
+            Some(self.position)
+        } else {
+            self.done = true;
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dense_grid;
+    use crate::sparse_grid;
+
+    #[test]
+    fn test_ray_stops_at_bounds() {
+        let ray = Ray::new(
+            Position::new(1, 1),
+            Compass::SouthEast,
+            Position::new(0, 0),
+            Position::new(3, 4),
+        );
+        assert_eq!(
+            ray.collect::<Vec<_>>(),
+            vec![Position::new(2, 2), Position::new(3, 3)]
+        );
+
+        let ray = Ray::new(
+            Position::new(0, 0),
+            Position::new(3, 1),
+            Position::new(0, 0),
+            Position::new(10, 10),
+        );
+        assert_eq!(ray.count(), 3);
+
+        let ray = Ray::new(
+            Position::new(2, 2),
+            Position::new(0, 0),
+            Position::new(0, 0),
+            Position::new(10, 10),
+        );
+        assert_eq!(ray.count(), 0);
+    }
+
+    #[test]
+    fn test_visible_from() {
+        let mut dense = dense_grid::Grid::from_origo(5, 5);
+        let mut sparse = sparse_grid::Grid::new();
+        for &(x, y) in &[(0, 0), (2, 0), (4, 2), (0, 4), (4, 4), (3, 1)] {
+            dense.insert(Position::new(x, y), '#');
+            sparse.insert(Position::new(x, y), '#');
+        }
+        sparse.insert(Position::new(2, 4), '.');
+
+        let center = Position::new(2, 2);
+        let dense_hits = dense
+            .visible_from(center, |&c| c != '#')
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        let sparse_hits = sparse
+            .visible_from(center, |&c| c != '#')
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        let expected = vec![
+            Position::new(2, 0),
+            Position::new(3, 1),
+            Position::new(4, 2),
+            Position::new(4, 4),
+            Position::new(0, 4),
+            Position::new(0, 0),
+        ];
+        assert_eq!(dense_hits, expected);
+        assert_eq!(sparse_hits, expected);
+
+        let still = Position::new(0, 0);
+        assert_eq!(dense.first_visible(center, still, |&c| c != '#'), None);
+        assert_eq!(sparse.first_visible(center, still, |&c| c != '#'), None);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::grid::{Connectivity, GridLike};
+use crate::position::*;
+use hashbrown::{HashMap, HashSet};
+use std::collections::VecDeque;
+
+/// All positions reachable from `seed` through positions where `passable` holds
+///
+/// The fill stays within the bounds of the grid. The predicate is given `None` for
+/// positions without a value, such as empty cells in a sparse grid. If the seed itself
+/// is not passable, the result is empty.
+pub fn flood_fill<G, F>(
+    grid: &G,
+    seed: Position,
+    connectivity: Connectivity,
+    passable: F,
+) -> HashSet<Position>
+where
+    G: GridLike,
+    F: Fn(Position, Option<&G::Item>) -> bool,
+{
+    let mut filled = HashSet::new();
+    if !grid.in_bounds(seed) || !passable(seed, grid.get(seed)) {
+        return filled;
+    }
+    let mut queue = VecDeque::new();
+    filled.insert(seed);
+    queue.push_back(seed);
+    while let Some(position) = queue.pop_front() {
+        for neighbour in grid.neighbours(position, connectivity) {
+            if !filled.contains(&neighbour) && passable(neighbour, grid.get(neighbour)) {
+                filled.insert(neighbour);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    filled
+}
+
+/// All positions in the grid that can be reached from outside the bounds
+///
+/// The fill starts in a one cell wide frame around the grid, where everything is passable,
+/// and moves into the grid through positions where `passable` holds. Only positions
+/// inside the bounds are returned.
+pub fn flood_fill_outside<G, F>(
+    grid: &G,
+    connectivity: Connectivity,
+    passable: F,
+) -> HashSet<Position>
+where
+    G: GridLike,
+    F: Fn(Position, Option<&G::Item>) -> bool,
+{
+    let (min_pos, max_pos) = grid.bounds();
+    let min_pos = min_pos - Position::new(1, 1);
+    let max_pos = max_pos + Position::new(1, 1);
+    let in_frame = |position: Position| {
+        min_pos.x <= position.x
+            && position.x <= max_pos.x
+            && min_pos.y <= position.y
+            && position.y <= max_pos.y
+    };
+
+    let mut reached = HashSet::new();
+    let mut queue = VecDeque::new();
+    reached.insert(min_pos);
+    queue.push_back(min_pos);
+    while let Some(position) = queue.pop_front() {
+        for neighbour in connectivity.neighbours(position) {
+            if in_frame(neighbour)
+                && !reached.contains(&neighbour)
+                && (!grid.in_bounds(neighbour) || passable(neighbour, grid.get(neighbour)))
+            {
+                reached.insert(neighbour);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    reached
+        .into_iter()
+        .filter(|&position| grid.in_bounds(position))
+        .collect()
+}
+
+/// The passable positions in the grid that can not be reached from outside the bounds
+pub fn enclosed<G, F>(grid: &G, connectivity: Connectivity, passable: F) -> HashSet<Position>
+where
+    G: GridLike,
+    F: Fn(Position, Option<&G::Item>) -> bool,
+{
+    let outside = flood_fill_outside(grid, connectivity, &passable);
+    let (min_pos, max_pos) = grid.bounds();
+    (min_pos.y..=max_pos.y)
+        .flat_map(|y| (min_pos.x..=max_pos.x).map(move |x| Position::new(x, y)))
+        .filter(|position| !outside.contains(position))
+        .filter(|&position| passable(position, grid.get(position)))
+        .collect()
+}
+
+/// The directed boundary edges of the region, from start corner to end corners
+///
+/// Corner (x, y) is the top-left corner of the cell at (x, y). The edges go clockwise
+/// around the region, so the region is always to the right of an edge.
+fn boundary_edges(region: &HashSet<Position>) -> HashMap<Position, Vec<Position>> {
+    let mut edges: HashMap<Position, Vec<Position>> = HashMap::new();
+    for &cell in region {
+        let top_left = cell;
+        let top_right = cell + Position::new(1, 0);
+        let bottom_right = cell + Position::new(1, 1);
+        let bottom_left = cell + Position::new(0, 1);
+        let sides = [
+            (Cardinal::North, top_left, top_right),
+            (Cardinal::East, top_right, bottom_right),
+            (Cardinal::South, bottom_right, bottom_left),
+            (Cardinal::West, bottom_left, top_left),
+        ];
+        for &(side, start, end) in &sides {
+            if !region.contains(&cell.step(side)) {
+                edges.entry(start).or_insert_with(Vec::new).push(end);
+            }
+        }
+    }
+    edges
+}
+
+/// The length of the boundary of the region, including the boundaries of any holes
+pub fn perimeter(region: &HashSet<Position>) -> usize {
+    boundary_edges(region).values().map(Vec::len).sum()
+}
+
+/// The closed outlines of the region as lists of corners
+///
+/// Corner (x, y) is the top-left corner of the cell at (x, y), and each outline goes
+/// clockwise around the region with only the corners where the outline turns included.
+/// There is one outline for the outside of each connected part and one for each hole.
+/// Parts of the region that only touch diagonally get separate outlines, while holes
+/// that touch diagonally share an outline.
+pub fn outline(region: &HashSet<Position>) -> Vec<Vec<Position>> {
+    let mut edges = boundary_edges(region);
+    let mut starts = edges.keys().cloned().collect::<Vec<_>>();
+    starts.sort();
+
+    let mut outlines = Vec::new();
+    for start in starts {
+        while let Some(first) = take_edge(&mut edges, start, None) {
+            let mut corners = vec![start];
+            let mut previous = start;
+            let mut current = first;
+            while current != start {
+                corners.push(current);
+                let next = take_edge(&mut edges, current, Some(current - previous))
+                    .expect("Boundary edges form closed loops");
+                previous = current;
+                current = next;
+            }
+            outlines.push(only_turns(&corners));
+        }
+    }
+    outlines
+}
+
+/// The number of straight sides of the region, including the sides of any holes
+pub fn sides(region: &HashSet<Position>) -> usize {
+    outline(region).iter().map(Vec::len).sum()
+}
+
+/// Remove the edge from `corner` that follows an edge in direction `incoming`
+///
+/// Where a region touches itself diagonally a corner has two outgoing edges, and turning
+/// right keeps following the same part of the region.
+fn take_edge(
+    edges: &mut HashMap<Position, Vec<Position>>,
+    corner: Position,
+    incoming: Option<Position>,
+) -> Option<Position> {
+    let ends = edges.get_mut(&corner)?;
+    let index = incoming
+        .and_then(|incoming| {
+            ends.iter()
+                .position(|&end| end - corner == incoming.rotate(Turn::Right))
+        })
+        .unwrap_or(0);
+    let end = ends.swap_remove(index);
+    if ends.is_empty() {
+        edges.remove(&corner);
+    }
+    Some(end)
+}
+
+/// The corners of the closed loop where the direction changes
+fn only_turns(corners: &[Position]) -> Vec<Position> {
+    let len = corners.len();
+    (0..len)
+        .filter(|&index| {
+            let previous = corners[(index + len - 1) % len];
+            let current = corners[index];
+            let next = corners[(index + 1) % len];
+            current - previous != next - current
+        })
+        .map(|index| corners[index])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dense_grid;
+    use crate::sparse_grid;
+
+    fn char_grid(rows: &[&str]) -> dense_grid::Grid<char> {
+        let mut grid = dense_grid::Grid::from_origo(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                grid[(x, y)] = ch;
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn test_region_measures() {
+        let grid = char_grid(&["AAAAAA", "AAABBA", "AAABBA", "ABBAAA", "ABBAAA", "AAAAAA"]);
+        let region = flood_fill(&grid, Position::origo(), Connectivity::Four, |_, v| {
+            v == Some(&'A')
+        });
+        assert_eq!(region.len(), 28);
+        assert_eq!(perimeter(&region), 40);
+        assert_eq!(sides(&region), 12);
+        assert_eq!(outline(&region).len(), 2);
+
+        let b = flood_fill(&grid, Position::new(1, 3), Connectivity::Four, |_, v| {
+            v == Some(&'B')
+        });
+        assert_eq!(
+            outline(&b),
+            vec![vec![
+                Position::new(1, 3),
+                Position::new(3, 3),
+                Position::new(3, 5),
+                Position::new(1, 5)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_diagonal_parts() {
+        let region = [Position::new(0, 0), Position::new(1, 1)]
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+        assert_eq!(outline(&region).len(), 2);
+        assert_eq!(sides(&region), 8);
+        assert_eq!(perimeter(&region), 8);
+    }
+
+    #[test]
+    fn test_enclosed() {
+        let grid = char_grid(&["#####", "#..#.", "#..#.", "####."]);
+        let inside = enclosed(&grid, Connectivity::Four, |_, v| v == Some(&'.'));
+        assert_eq!(inside.len(), 4);
+        assert!(inside.contains(&Position::new(1, 1)));
+        assert!(!inside.contains(&Position::new(4, 1)));
+
+        let mut sparse = sparse_grid::Grid::new();
+        for position in connect8(Position::new(5, 5)) {
+            sparse.insert(position, '#');
+        }
+        let inside = enclosed(&sparse, Connectivity::Four, |_, v| v.is_none());
+        assert_eq!(
+            inside.into_iter().collect::<Vec<_>>(),
+            vec![Position::new(5, 5)]
+        );
+        let still_enclosed = enclosed(&sparse, Connectivity::Eight, |_, v| v.is_none());
+        assert_eq!(still_enclosed.len(), 1);
+
+        let diamond = char_grid(&["..#..", ".#.#.", "#...#", ".#.#.", "..#.."]);
+        let inside = enclosed(&diamond, Connectivity::Four, |_, v| v == Some(&'.'));
+        assert_eq!(inside.len(), 5);
+        assert!(inside.contains(&Position::new(2, 2)));
+        let leaky = enclosed(&diamond, Connectivity::Eight, |_, v| v == Some(&'.'));
+        assert!(leaky.is_empty());
+    }
+}
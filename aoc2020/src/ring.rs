@@ -0,0 +1,300 @@
+use failure::{bail, format_err, Error};
+use hashbrown::HashMap;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// A circle of distinct values with a cursor, where removing and inserting values is O(1)
+///
+/// Values keep the slot they were first given, and each slot stores the slot of the value
+/// after it. Removed values keep their slot, so they can be inserted again without
+/// allocating.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # fn main() -> Result<(), Error> {
+/// use aoc2020::ring::Ring;
+/// let mut ring = Ring::new(vec![3, 8, 9, 1, 2, 5, 4, 6, 7])?;
+/// let picked = ring.remove_after(3);
+/// assert_eq!(picked, vec![8, 9, 1]);
+/// ring.insert_after(&2, picked)?;
+/// ring.advance();
+/// assert_eq!(ring.to_string(), "(2) 8 9 1 5 4 6 7 3");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ring<T: Hash + Eq + Clone + Debug> {
+    values: Vec<T>,
+    /// The slot of the value after each slot
+    next: Vec<usize>,
+    /// Whether the value in each slot is in the ring
+    linked: Vec<bool>,
+    slots: HashMap<T, usize>,
+    current: usize,
+    len: usize,
+}
+
+impl<T: Hash + Eq + Clone + Debug> Ring<T> {
+    /// A ring with the values in order, where the cursor is at the first value
+    pub fn new(values: Vec<T>) -> Result<Ring<T>, Error> {
+        if values.is_empty() {
+            bail!("A ring needs at least one value");
+        }
+        let len = values.len();
+        let mut slots = HashMap::with_capacity(len);
+        for (slot, value) in values.iter().enumerate() {
+            if slots.insert(value.clone(), slot).is_some() {
+                bail!("The value {:?} is in the ring twice", value);
+            }
+        }
+        Ok(Ring {
+            values,
+            next: (1..=len).map(|slot| slot % len).collect(),
+            linked: vec![true; len],
+            slots,
+            current: 0,
+            len,
+        })
+    }
+
+    /// The number of values in the ring
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always false, since the value at the cursor can not be removed
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// True if value is in the ring
+    pub fn contains(&self, value: &T) -> bool {
+        self.slot(value).is_some()
+    }
+
+    /// The slot of a value in the ring
+    fn slot(&self, value: &T) -> Option<usize> {
+        self.slots
+            .get(value)
+            .cloned()
+            .filter(|&slot| self.linked[slot])
+    }
+
+    /// The value at the cursor
+    pub fn current(&self) -> &T {
+        &self.values[self.current]
+    }
+
+    /// Move the cursor to the next value
+    pub fn advance(&mut self) {
+        self.current = self.next[self.current];
+    }
+
+    /// Move the cursor to value
+    pub fn move_to(&mut self, value: &T) -> Result<(), Error> {
+        self.current = self
+            .slot(value)
+            .ok_or_else(|| format_err!("The value {:?} is not in the ring", value))?;
+        Ok(())
+    }
+
+    /// The value after value, if value is in the ring
+    pub fn next_value(&self, value: &T) -> Option<&T> {
+        self.slot(value).map(|slot| &self.values[self.next[slot]])
+    }
+
+    /// Remove the `n` values after the cursor, and return them in order
+    ///
+    /// # Panics
+    ///
+    /// If `n` is not smaller than the length, as the cursor can not be removed.
+    pub fn remove_after(&mut self, n: usize) -> Vec<T> {
+        assert!(
+            n < self.len,
+            "Can not remove {} values after the cursor in a ring of {}",
+            n,
+            self.len
+        );
+        let mut removed = Vec::with_capacity(n);
+        let mut slot = self.next[self.current];
+        for _ in 0..n {
+            removed.push(self.values[slot].clone());
+            self.linked[slot] = false;
+            slot = self.next[slot];
+        }
+        self.next[self.current] = slot;
+        self.len -= n;
+        removed
+    }
+
+    /// Insert values in order after the value `after`
+    ///
+    /// The values can be new or removed earlier, but not already in the ring.
+    pub fn insert_after<I: IntoIterator<Item = T>>(
+        &mut self,
+        after: &T,
+        values: I,
+    ) -> Result<(), Error> {
+        let mut previous = self
+            .slot(after)
+            .ok_or_else(|| format_err!("Can not insert after {:?}, not in the ring", after))?;
+        let values = values.into_iter().collect::<Vec<_>>();
+        for (index, value) in values.iter().enumerate() {
+            if self.contains(value) || values[..index].contains(value) {
+                bail!("Can not insert {:?}, it is already in the ring", value);
+            }
+        }
+
+        let end = self.next[previous];
+        for value in values {
+            let slot = match self.slots.get(&value) {
+                Some(&slot) => slot,
+                None => {
+                    let slot = self.values.len();
+                    self.slots.insert(value.clone(), slot);
+                    self.values.push(value);
+                    self.next.push(end);
+                    self.linked.push(false);
+                    slot
+                }
+            };
+            self.linked[slot] = true;
+            self.next[previous] = slot;
+            previous = slot;
+            self.len += 1;
+        }
+        self.next[previous] = end;
+        Ok(())
+    }
+
+    /// The values in order, starting at the cursor
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            ring: self,
+            slot: self.current,
+            remaining: self.len,
+        }
+    }
+
+    /// The values in order, starting at value, if value is in the ring
+    pub fn iter_from(&self, value: &T) -> Option<Iter<'_, T>> {
+        self.slot(value).map(|slot| Iter {
+            ring: self,
+            slot,
+            remaining: self.len,
+        })
+    }
+}
+
+/// Iterator over the values of a `Ring`, going around once
+pub struct Iter<'a, T: Hash + Eq + Clone + Debug> {
+    ring: &'a Ring<T>,
+    slot: usize,
+    remaining: usize,
+}
+
+impl<'a, T: Hash + Eq + Clone + Debug> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let value = &self.ring.values[self.slot];
+        self.slot = self.ring.next[self.slot];
+        self.remaining -= 1;
+        Some(value)
+    }
+}
+
+/// The values from the cursor, with the cursor in parentheses, like `(3) 8 9 1`
+impl<T: Hash + Eq + Clone + Debug + fmt::Display> fmt::Display for Ring<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, value) in self.iter().enumerate() {
+            if index == 0 {
+                write!(f, "({})", value)?;
+            } else {
+                write!(f, " {}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(ring: &mut Ring<u32>, moves: usize) -> Result<(), Error> {
+        let max = ring.len() as u32;
+        for _ in 0..moves {
+            let current = *ring.current();
+            let picked = ring.remove_after(3);
+            let mut destination = current;
+            loop {
+                destination = if destination == 1 {
+                    max
+                } else {
+                    destination - 1
+                };
+                if !picked.contains(&destination) {
+                    break;
+                }
+            }
+            ring.insert_after(&destination, picked)?;
+            ring.advance();
+        }
+        Ok(())
+    }
+
+    fn labels(ring: &Ring<u32>) -> String {
+        ring.iter_from(&1)
+            .unwrap()
+            .skip(1)
+            .map(|value| value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_cups() -> Result<(), Error> {
+        let cups = vec![3, 8, 9, 1, 2, 5, 4, 6, 7];
+        let mut ring = Ring::new(cups.clone())?;
+        assert_eq!(ring.to_string(), "(3) 8 9 1 2 5 4 6 7");
+        play(&mut ring, 10)?;
+        assert_eq!(ring.to_string(), "(8) 3 7 4 1 9 2 6 5");
+        assert_eq!(labels(&ring), "92658374");
+
+        let mut ring = Ring::new(cups)?;
+        play(&mut ring, 100)?;
+        assert_eq!(labels(&ring), "67384529");
+        assert_eq!(ring.next_value(&1), Some(&6));
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_and_remove() -> Result<(), Error> {
+        let mut ring = Ring::new(vec!['a', 'b', 'c'])?;
+        assert!(Ring::new(vec!['a', 'a']).is_err());
+        assert!(Ring::<char>::new(vec![]).is_err());
+
+        ring.move_to(&'c')?;
+        assert_eq!(ring.remove_after(2), vec!['a', 'b']);
+        assert_eq!(ring.len(), 1);
+        assert!(!ring.contains(&'a'));
+        assert!(ring.insert_after(&'a', vec!['d']).is_err());
+        assert!(ring.insert_after(&'c', vec!['c']).is_err());
+        assert!(ring.insert_after(&'c', vec!['d', 'd']).is_err());
+        assert_eq!(ring.to_string(), "(c)");
+
+        ring.insert_after(&'c', vec!['b', 'x'])?;
+        ring.insert_after(&'x', vec!['a'])?;
+        assert_eq!(ring.to_string(), "(c) b x a");
+        assert_eq!(ring.iter_from(&'x').unwrap().collect::<String>(), "xacb");
+        assert!(ring.iter_from(&'z').is_none());
+        assert!(ring.move_to(&'z').is_err());
+        Ok(())
+    }
+}
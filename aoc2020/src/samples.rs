@@ -0,0 +1,179 @@
+use crate::solver::Solver;
+use crate::store::Store;
+use failure::Error;
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// A sample input with its expected output, if known
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub input: PathBuf,
+    pub output: PathBuf,
+}
+
+impl Sample {
+    pub fn expected(&self) -> Result<Option<String>, Error> {
+        if self.output.exists() {
+            Ok(Some(
+                fs::read_to_string(&self.output)?.trim_end().to_string(),
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// All `*.in` files in `directory`, sorted by name, with their sibling `*.out` files
+pub fn find_samples<P: AsRef<Path>>(directory: P) -> Result<Vec<Sample>, Error> {
+    let mut samples = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let input = entry?.path();
+        if input.extension() == Some("in".as_ref()) {
+            let name = input
+                .file_stem()
+                .expect("Input files have a name")
+                .to_string_lossy()
+                .to_string();
+            let output = input.with_extension("out");
+            samples.push(Sample {
+                name,
+                input,
+                output,
+            });
+        }
+    }
+    samples.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(samples)
+}
+
+/// Run the solver in-process on the input file and return the answer as a string
+pub fn run_sample<S: Solver>(sample: &Sample) -> Result<String, Error> {
+    let reader = BufReader::new(File::open(&sample.input)?);
+    Ok(S::solve_reader(reader)?.to_string())
+}
+
+enum Outcome {
+    Passed,
+    Blessed,
+    Ignored,
+    Failed(String),
+}
+
+fn check_sample<S: Solver>(sample: &Sample, bless: bool) -> Result<Outcome, Error> {
+    let expected = sample.expected()?;
+    if expected.is_none() && !bless {
+        return Ok(Outcome::Ignored);
+    }
+    let actual = match panic::catch_unwind(|| run_sample::<S>(sample)) {
+        Ok(result) => result?,
+        Err(_) => return Ok(Outcome::Failed("solver panicked".to_string())),
+    };
+    let actual = actual.trim_end().to_string();
+    if expected.as_ref() == Some(&actual) {
+        Ok(Outcome::Passed)
+    } else if bless {
+        fs::write(&sample.output, format!("{}\n", actual))?;
+        Ok(Outcome::Blessed)
+    } else {
+        Ok(Outcome::Failed(format!(
+            "expected: {}\n  actual: {}",
+            expected.unwrap_or_default(),
+            actual
+        )))
+    }
+}
+
+/// Test main that runs the solver on every sample in `directory`
+///
+/// Used from a test target with `harness = false`, so that each `*.in` file with a
+/// sibling `*.out` file becomes a test. Inputs without an expected output are ignored.
+/// Arguments that are not flags filter the samples by name, and with `--bless` the
+/// `*.out` files are written with the actual answers instead of being checked.
+///
+/// For the data directory of a day crate, the samples come from the `Store`, so part 2
+/// is also checked on the input of part 1.
+pub fn main<S: Solver>(directory: &str) {
+    let mut bless = false;
+    let mut list = false;
+    let mut filters = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bless" => bless = true,
+            "--list" => list = true,
+            "--skip" | "--test-threads" | "--color" | "--format" | "-Z" => {
+                args.next();
+            }
+            _ if arg.starts_with('-') => {}
+            _ => filters.push(arg),
+        }
+    }
+
+    let samples = match Store::for_data_dir(directory) {
+        Some((store, day, part)) => store.samples(day, part),
+        None => find_samples(directory),
+    };
+    let samples = samples
+        .unwrap_or_else(|error| panic!("Could not read samples in {}: {}", directory, error))
+        .into_iter()
+        .filter(|sample| filters.is_empty() || filters.iter().any(|f| sample.name.contains(f)))
+        .collect::<Vec<_>>();
+
+    if list {
+        for sample in &samples {
+            println!("{}: test", sample.name);
+        }
+        return;
+    }
+
+    println!("\nrunning {} tests", samples.len());
+    let mut passed = 0;
+    let mut ignored = 0;
+    let mut failures = Vec::new();
+    for sample in &samples {
+        let outcome = check_sample::<S>(sample, bless)
+            .unwrap_or_else(|error| Outcome::Failed(format!("error: {}", error)));
+        let status = match outcome {
+            Outcome::Passed => {
+                passed += 1;
+                "ok"
+            }
+            Outcome::Blessed => {
+                passed += 1;
+                "blessed"
+            }
+            Outcome::Ignored => {
+                ignored += 1;
+                "ignored, no expected output"
+            }
+            Outcome::Failed(message) => {
+                failures.push((sample.name.clone(), message));
+                "FAILED"
+            }
+        };
+        println!("test {} ... {}", sample.name, status);
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (name, message) in &failures {
+            println!("\n---- {} ----\n  {}", name, message.replace('\n', "\n  "));
+        }
+    }
+    println!(
+        "\ntest result: {}. {} passed; {} failed; {} ignored\n",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        passed,
+        failures.len(),
+        ignored
+    );
+    if !failures.is_empty() {
+        process::exit(101);
+    }
+}
@@ -0,0 +1,83 @@
+use failure::Error;
+use std::fmt::Display;
+use std::io;
+use std::io::BufRead;
+use std::process;
+
+/// A puzzle solution, split into reading the input and solving the puzzle
+///
+/// Each day implements this in its library, so that the binary, the sample tests and
+/// other tools can all run the solution in the same way.
+///
+/// # Examples
+///
+/// ```
+/// # use failure::Error;
+/// # use std::io::BufRead;
+/// use aoc2020::solver::Solver;
+///
+/// struct Sum;
+///
+/// impl Solver for Sum {
+///     type Input = Vec<i32>;
+///     type Output = i32;
+///
+///     fn read_input<R: BufRead>(reader: R) -> Result<Vec<i32>, Error> {
+///         let mut result = Vec::new();
+///         for line in reader.lines() {
+///             result.push(line?.trim().parse()?);
+///         }
+///         Ok(result)
+///     }
+///
+///     fn solve(input: &mut Vec<i32>) -> Result<i32, Error> {
+///         Ok(input.iter().sum())
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Error> {
+/// assert_eq!(Sum::solve_str("1\n2\n3\n")?, 6);
+/// # Ok(())
+/// # }
+/// ```
+pub trait Solver {
+    type Input;
+    type Output: Display;
+
+    fn read_input<R: BufRead>(reader: R) -> Result<Self::Input, Error>;
+
+    fn solve(input: &mut Self::Input) -> Result<Self::Output, Error>;
+
+    /// Read the input from `reader` and solve the puzzle
+    fn solve_reader<R: BufRead>(reader: R) -> Result<Self::Output, Error> {
+        let mut input = Self::read_input(reader)?;
+        Self::solve(&mut input)
+    }
+
+    /// Solve the puzzle for an input given as a string, for use in tests and benchmarks
+    fn solve_str(input: &str) -> Result<Self::Output, Error> {
+        Self::solve_reader(input.as_bytes())
+    }
+}
+
+fn run<S: Solver>() -> Result<(), Error> {
+    let stdin = io::stdin();
+    let output = S::solve_reader(stdin.lock())?;
+
+    println!("{}", output);
+    Ok(())
+}
+
+/// Solve the puzzle for standard input and print the answer, used as the main of each day
+pub fn main<S: Solver>() {
+    match run::<S>() {
+        Ok(()) => process::exit(0),
+        Err(error) => {
+            eprintln!("Error while solving problem: {}", error);
+            for cause in error.iter_causes() {
+                eprintln!("{}", cause)
+            }
+            process::exit(1)
+        }
+    }
+}
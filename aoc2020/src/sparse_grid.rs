@@ -1,23 +1,52 @@
+use crate::dense_grid;
+use crate::grid::{cell_terminator, display_cells, GridLike};
+use crate::matrix::Matrix;
 use crate::position::*;
+use crate::ray::Ray;
+use failure::Error;
 use hashbrown::HashMap;
-use std::cmp::max;
-use std::cmp::min;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::ops::Index;
 use std::ops::IndexMut;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// A grid that only stores the positions that have a value.
+///
+/// The bounds are the smallest rectangle (inclusive) that contains all the values. They
+/// are kept exact through `insert` and `remove` by counting the values in each row and
+/// column, so `values` should only be changed through those.
+#[derive(Debug, Clone)]
 pub struct Grid<T>
 where
     T: Debug + Clone + Eq,
 {
     pub values: HashMap<Position, T>,
-    pub min_x: Scalar,
-    pub min_y: Scalar,
-    pub max_x: Scalar,
-    pub max_y: Scalar,
+    /// The number of values with each x coordinate
+    columns: BTreeMap<Scalar, usize>,
+    /// The number of values with each y coordinate
+    rows: BTreeMap<Scalar, usize>,
+}
+
+/// Count one more or one less value at `key`, removing the key when there are none left
+fn count(counts: &mut BTreeMap<Scalar, usize>, key: Scalar, added: bool) {
+    if added {
+        *counts.entry(key).or_insert(0) += 1;
+    } else if let Some(count) = counts.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
+}
+
+/// The smallest and largest keys, if there are any
+fn extent(counts: &BTreeMap<Scalar, usize>) -> Option<(Scalar, Scalar)> {
+    let first = counts.keys().next()?;
+    let last = counts.keys().next_back()?;
+    Some((*first, *last))
 }
 
 impl<T> Grid<T>
@@ -27,10 +56,8 @@ where
     pub fn new() -> Grid<T> {
         Grid {
             values: HashMap::with_capacity(256),
-            min_x: 0,
-            min_y: 0,
-            max_x: 0,
-            max_y: 0,
+            columns: BTreeMap::new(),
+            rows: BTreeMap::new(),
         }
     }
 
@@ -42,31 +69,181 @@ where
         max_y: Scalar,
     ) -> Grid<T> {
         let mut grid = Grid::new();
-        for x in min_x..max_x {
-            for y in min_y..max_y {
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
                 grid.insert((x, y).into(), value.clone());
             }
         }
         grid
     }
 
-    fn update_bounds(&mut self, position: Position) {
-        self.min_x = min(self.min_x, position.x);
-        self.min_y = min(self.min_y, position.y);
-        self.max_x = max(self.max_x, position.x);
-        self.max_y = max(self.max_y, position.y);
+    /// The inclusive bounds of the grid as the (min, max) corners, both at origo if the
+    /// grid is empty
+    pub fn bounds(&self) -> (Position, Position) {
+        match (extent(&self.columns), extent(&self.rows)) {
+            (Some((min_x, max_x)), Some((min_y, max_y))) => {
+                (Position::new(min_x, min_y), Position::new(max_x, max_y))
+            }
+            _ => (Position::origo(), Position::origo()),
+        }
     }
 
     pub fn insert(&mut self, position: Position, value: T) {
-        self.update_bounds(position);
-        self.values.insert(position, value);
+        if self.values.insert(position, value).is_none() {
+            count(&mut self.columns, position.x, true);
+            count(&mut self.rows, position.y, true);
+        }
+    }
+
+    /// Remove the value at `position`, if any, shrinking the bounds if it was on the edge
+    pub fn remove(&mut self, position: Position) -> Option<T> {
+        let removed = self.values.remove(&position);
+        if removed.is_some() {
+            count(&mut self.columns, position.x, false);
+            count(&mut self.rows, position.y, false);
+        }
+        removed
     }
 
     pub fn get(&self, position: Position) -> Option<&T> {
         self.values.get(&position)
     }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The occupied cells in reading order
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        let mut cells = self
+            .values
+            .iter()
+            .map(|(&position, value)| (position, value))
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|&(position, _)| position);
+        cells.into_iter()
+    }
+
+    pub fn in_bounds(&self, position: Position) -> bool {
+        let (min_pos, max_pos) = self.bounds();
+        min_pos.x <= position.x
+            && position.x <= max_pos.x
+            && min_pos.y <= position.y
+            && position.y <= max_pos.y
+    }
+
+    /// The positions seen when walking from `from` (exclusive) in `direction`
+    ///
+    /// The walk stops when leaving the bounds of the grid.
+    pub fn ray<D>(&self, from: Position, direction: D) -> Ray<D>
+    where
+        D: Copy,
+        Position: Step<D>,
+    {
+        let (min_pos, max_pos) = self.bounds();
+        Ray::new(from, direction, min_pos, max_pos)
+    }
+
+    /// The first non-empty cell seen when looking from `from` (exclusive) in `direction`
+    ///
+    /// Positions without a value count as empty. Returns `None` if only empty cells are
+    /// seen before leaving the bounds of the grid.
+    pub fn first_visible<D, F>(
+        &self,
+        from: Position,
+        direction: D,
+        is_empty: F,
+    ) -> Option<(Position, &T)>
+    where
+        D: Copy,
+        Position: Step<D>,
+        F: Fn(&T) -> bool,
+    {
+        self.ray(from, direction)
+            .filter_map(|position| self.get(position).map(|value| (position, value)))
+            .find(|(_, value)| !is_empty(value))
+    }
+
+    /// The first non-empty cell in each of the eight directions from `from`
+    ///
+    /// Directions are checked in the same order as `connect8`, directions where no
+    /// non-empty cell is seen are skipped.
+    pub fn visible_from<'a, F>(
+        &'a self,
+        from: Position,
+        is_empty: F,
+    ) -> impl Iterator<Item = (Position, &'a T)> + 'a
+    where
+        F: Fn(&T) -> bool + 'a,
+    {
+        connect8(Position::origo())
+            .filter_map(move |direction| self.first_visible(from, direction, &is_empty))
+    }
+}
+
+impl<T> GridLike for Grid<T>
+where
+    T: Debug + Clone + Eq,
+{
+    type Item = T;
+
+    fn get(&self, position: Position) -> Option<&T> {
+        self.values.get(&position)
+    }
+
+    fn set(&mut self, position: Position, value: T) {
+        self.insert(position, value);
+    }
+
+    fn bounds(&self) -> (Position, Position) {
+        Grid::bounds(self)
+    }
+
+    fn positions(&self) -> Vec<Position> {
+        self.iter().map(|(position, _)| position).collect()
+    }
 }
 
+impl<T> From<&dense_grid::Grid<T>> for Grid<T>
+where
+    T: Debug + Clone + Eq + Default,
+{
+    fn from(dense: &dense_grid::Grid<T>) -> Self {
+        let mut grid = Grid::new();
+        for position in dense.positions() {
+            grid.insert(position, dense[position].clone());
+        }
+        grid
+    }
+}
+
+/// All cells of the matrix are stored, so that the bounds are kept
+impl From<&Matrix> for Grid<bool> {
+    fn from(matrix: &Matrix) -> Self {
+        let mut grid = Grid::new();
+        for position in matrix.positions() {
+            grid.insert(position, matrix[(position.x as usize, position.y as usize)]);
+        }
+        grid
+    }
+}
+
+impl<T> PartialEq for Grid<T>
+where
+    T: Debug + Clone + Eq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // The bounds are given by the values
+        self.values == other.values
+    }
+}
+
+impl<T> Eq for Grid<T> where T: Debug + Clone + Eq {}
+
 impl<T> Default for Grid<T>
 where
     T: Debug + Clone + Eq,
@@ -136,6 +313,8 @@ where
     }
 }
 
+/// Values are padded to the widest value, and each followed by a space when wider than one
+/// character, so that the output can be parsed back with `FromStr`
 impl<T> Display for Grid<T>
 where
     T: Display + Debug + Clone + Eq,
@@ -144,20 +323,128 @@ where
         let width = self
             .values
             .values()
-            .map(|v| format!("{}", v).len())
+            .map(|v| format!("{}", v).chars().count())
             .max()
             .unwrap_or(1);
         let filler = " ".repeat(width);
-        for y in self.min_y..self.max_y {
-            for x in self.min_x..self.max_x {
+        let terminator = cell_terminator(width);
+        if self.values.is_empty() {
+            return Ok(());
+        }
+        let (min_pos, max_pos) = self.bounds();
+        for y in min_pos.y..=max_pos.y {
+            for x in min_pos.x..=max_pos.x {
                 if let Some(v) = self.get((x, y).into()) {
                     write!(f, "{:width$}", v, width = width)?;
                 } else {
                     write!(f, "{}", filler)?;
                 }
+                write!(f, "{}", terminator)?;
             }
             writeln!(f)?;
         }
         Ok(())
     }
 }
+
+/// Parses the `Display` output of a grid, with the minimum corner at (0, 0)
+///
+/// Blank cells are treated as positions without a value.
+impl<T> FromStr for Grid<T>
+where
+    T: FromStr + Debug + Clone + Eq,
+    <T as FromStr>::Err: Display,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grid = Grid::new();
+        for (y, row) in display_cells(s).iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.trim().is_empty() {
+                    continue;
+                }
+                let position = Position::from((x, y));
+                let value = cell.parse::<T>().map_err(|err| {
+                    failure::format_err!(
+                        "Could not parse \"{}\" at {}, reason is {}",
+                        cell,
+                        position,
+                        err
+                    )
+                })?;
+                grid.insert(position, value);
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_shrinks_bounds() {
+        let mut grid = Grid::new();
+        grid.insert(Position::new(-1, 2), 'a');
+        grid.insert(Position::new(3, 4), 'b');
+        grid.insert(Position::new(1, 3), 'c');
+        assert_eq!(grid.bounds(), (Position::new(-1, 2), Position::new(3, 4)));
+
+        assert_eq!(grid.remove(Position::new(3, 4)), Some('b'));
+        assert_eq!(grid.remove(Position::new(3, 4)), None);
+        assert_eq!(grid.bounds(), (Position::new(-1, 2), Position::new(1, 3)));
+        assert!(!grid.in_bounds(Position::new(2, 3)));
+
+        grid.insert(Position::new(1, 3), 'd');
+        assert_eq!(grid.remove(Position::new(-1, 2)), Some('a'));
+        assert_eq!(grid.bounds(), (Position::new(1, 3), Position::new(1, 3)));
+        assert_eq!(grid.remove(Position::new(1, 3)), Some('d'));
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), (Position::origo(), Position::origo()));
+        grid.insert(Position::new(5, -5), 'e');
+        assert_eq!(grid.bounds(), (Position::new(5, -5), Position::new(5, -5)));
+    }
+
+    #[test]
+    fn test_first_visible() {
+        let mut grid = Grid::new();
+        grid.insert(Position::new(0, 0), '#');
+        grid.insert(Position::new(2, 0), '#');
+        grid.insert(Position::new(1, 2), 'L');
+        grid.insert(Position::new(3, 3), '.');
+        let from = Position::new(2, 3);
+        let seat = |&c: &char| c == '.';
+        assert_eq!(
+            grid.first_visible(from, Compass::North, seat),
+            Some((Position::new(2, 0), &'#'))
+        );
+        assert_eq!(grid.first_visible(from, Compass::East, seat), None);
+        assert_eq!(grid.first_visible(from, Compass::South, seat), None);
+        assert_eq!(
+            grid.visible_from(from, seat).collect::<Vec<_>>(),
+            vec![(Position::new(2, 0), &'#'), (Position::new(1, 2), &'L')]
+        );
+    }
+
+    #[test]
+    fn test_display_is_inclusive() {
+        let mut grid = Grid::new();
+        grid.insert(Position::new(1, 1), '#');
+        grid.insert(Position::new(3, 2), '#');
+        assert_eq!(format!("{}", grid), "#  \n  #\n");
+
+        let filled = Grid::filled(&'.', 0, 0, 1, 1);
+        assert_eq!(format!("{}", filled), "..\n..\n");
+        assert_eq!(
+            filled.iter().map(|(p, _)| p).collect::<Vec<_>>(),
+            vec![
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(0, 1),
+                Position::new(1, 1)
+            ]
+        );
+    }
+}
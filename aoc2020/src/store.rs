@@ -0,0 +1,375 @@
+use crate::day_and_part::day_and_part;
+use crate::samples::{find_samples, Sample};
+use failure::{bail, format_err, Error};
+use std::fmt;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use strum_macros::EnumString;
+
+/// The name of the puzzle input in the data directory of a day
+pub const INPUT: &str = "puzzle1";
+/// The file in the data directory of a day where submitted answers are recorded
+pub const LEDGER: &str = "answers.tsv";
+
+/// The result of submitting an answer
+#[derive(EnumString, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Verdict {
+    #[strum(serialize = "correct")]
+    Correct,
+    #[strum(serialize = "too-high")]
+    TooHigh,
+    #[strum(serialize = "too-low")]
+    TooLow,
+    #[strum(serialize = "wrong")]
+    Wrong,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// A submitted answer and its verdict
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Guess {
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+/// The submitted answers for one puzzle, in submission order
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Ledger {
+    pub guesses: Vec<Guess>,
+}
+
+impl Ledger {
+    /// The answer that was accepted, if any
+    pub fn correct(&self) -> Option<&str> {
+        self.guesses
+            .iter()
+            .find(|guess| guess.verdict == Verdict::Correct)
+            .map(|guess| guess.answer.as_str())
+    }
+
+    /// Check an answer against what is known, before submitting it
+    ///
+    /// Returns `Correct` if the answer is the accepted one and `None` if it may be right.
+    /// An answer that was rejected before, that is outside the bounds given by earlier
+    /// too high or too low guesses, or that differs from the accepted answer, is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aoc2020::store::{Guess, Ledger, Verdict};
+    /// let ledger = Ledger {
+    ///     guesses: vec![Guess { answer: "120".to_string(), verdict: Verdict::TooHigh }],
+    /// };
+    /// assert!(ledger.check("120").is_err());
+    /// assert!(ledger.check("150").is_err());
+    /// assert_eq!(ledger.check("110").unwrap(), None);
+    /// ```
+    pub fn check(&self, answer: &str) -> Result<Option<Verdict>, Error> {
+        if let Some(correct) = self.correct() {
+            if correct == answer {
+                return Ok(Some(Verdict::Correct));
+            }
+            bail!("{} is wrong, the correct answer is {}", answer, correct);
+        }
+        let number = answer.parse::<i128>().ok();
+        for guess in &self.guesses {
+            if guess.answer == answer {
+                bail!("{} was already submitted and is {}", answer, guess.verdict);
+            }
+            let bound = guess.answer.parse::<i128>().ok();
+            match (number, bound, guess.verdict) {
+                (Some(number), Some(bound), Verdict::TooHigh) if number > bound => {
+                    bail!("{} is too high, since {} is too high", answer, bound)
+                }
+                (Some(number), Some(bound), Verdict::TooLow) if number < bound => {
+                    bail!("{} is too low, since {} is too low", answer, bound)
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Inputs, samples, and answers for each day and part, in the data directories of the
+/// crates of the workspace
+///
+/// The data for part 2 of day 5 is in `day5-2/data`. The puzzle input is `puzzle1.in`, and
+/// part 2 uses the input of part 1 if it has none of its own. The confirmed answer is
+/// `puzzle1.out`, so that the samples test checks it, and all submitted answers are
+/// recorded in `answers.tsv`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    /// A store for the workspace in `root`
+    pub fn new<P: AsRef<Path>>(root: P) -> Store {
+        Store {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The store and puzzle for a data directory named like `day5-2/data`
+    pub fn for_data_dir<P: AsRef<Path>>(directory: P) -> Option<(Store, u32, u32)> {
+        let directory = directory.as_ref();
+        let name = directory.parent()?.file_name()?.to_str()?;
+        let (day, part) = day_and_part(name)?;
+        let root = directory.parent()?.parent()?;
+        Some((Store::new(root), day, part))
+    }
+
+    /// The data directory for a day and part
+    pub fn directory(&self, day: u32, part: u32) -> PathBuf {
+        self.root.join(format!("day{}-{}", day, part)).join("data")
+    }
+
+    fn existing_directory(&self, day: u32, part: u32) -> Result<PathBuf, Error> {
+        let directory = self.directory(day, part);
+        if !directory.parent().is_some_and(Path::is_dir) {
+            bail!("There is no crate for day {} part {}", day, part);
+        }
+        fs::create_dir_all(&directory)?;
+        Ok(directory)
+    }
+
+    /// The path of the puzzle input, falling back to the input of part 1 for part 2
+    pub fn input_path(&self, day: u32, part: u32) -> Option<PathBuf> {
+        let own = self.directory(day, part).join(INPUT).with_extension("in");
+        let first = self.directory(day, 1).join(INPUT).with_extension("in");
+        if own.exists() {
+            Some(own)
+        } else if part == 2 && first.exists() {
+            Some(first)
+        } else {
+            None
+        }
+    }
+
+    /// The puzzle input
+    pub fn input(&self, day: u32, part: u32) -> Result<String, Error> {
+        let path = self
+            .input_path(day, part)
+            .ok_or_else(|| format_err!("No input for day {} part {}", day, part))?;
+        Ok(fs::read_to_string(path)?)
+    }
+
+    /// Save the puzzle input
+    pub fn save_input(&self, day: u32, part: u32, input: &str) -> Result<PathBuf, Error> {
+        let path = self
+            .existing_directory(day, part)?
+            .join(INPUT)
+            .with_extension("in");
+        fs::write(&path, input)?;
+        Ok(path)
+    }
+
+    /// The samples and the puzzle input with their expected answers
+    ///
+    /// If part 2 has no input of its own, the input of part 1 is used with the answer
+    /// of part 2.
+    pub fn samples(&self, day: u32, part: u32) -> Result<Vec<Sample>, Error> {
+        let directory = self.directory(day, part);
+        let mut samples = if directory.is_dir() {
+            find_samples(&directory)?
+        } else {
+            Vec::new()
+        };
+        if !samples.iter().any(|sample| sample.name == INPUT) {
+            if let Some(input) = self.input_path(day, part) {
+                samples.push(Sample {
+                    name: INPUT.to_string(),
+                    input,
+                    output: directory.join(INPUT).with_extension("out"),
+                });
+                samples.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+        Ok(samples)
+    }
+
+    /// Add a sample input with its answer, if known, as the next `sampleN`
+    pub fn add_sample(
+        &self,
+        day: u32,
+        part: u32,
+        input: &str,
+        answer: Option<&str>,
+    ) -> Result<Sample, Error> {
+        let directory = self.existing_directory(day, part)?;
+        let name = (1..)
+            .map(|index| format!("sample{}", index))
+            .find(|name| !directory.join(name).with_extension("in").exists())
+            .expect("There is a free sample name");
+        let sample = Sample {
+            input: directory.join(&name).with_extension("in"),
+            output: directory.join(&name).with_extension("out"),
+            name,
+        };
+        fs::write(&sample.input, input)?;
+        if let Some(answer) = answer {
+            fs::write(&sample.output, format!("{}\n", answer))?;
+        }
+        Ok(sample)
+    }
+
+    /// The confirmed answer
+    pub fn answer(&self, day: u32, part: u32) -> Result<Option<String>, Error> {
+        let path = self.directory(day, part).join(INPUT).with_extension("out");
+        if path.exists() {
+            Ok(Some(fs::read_to_string(path)?.trim_end().to_string()))
+        } else {
+            Ok(self.ledger(day, part)?.correct().map(str::to_string))
+        }
+    }
+
+    /// The submitted answers
+    ///
+    /// A confirmed answer in `puzzle1.out` without a ledger entry counts as correct.
+    pub fn ledger(&self, day: u32, part: u32) -> Result<Ledger, Error> {
+        let directory = self.directory(day, part);
+        let mut ledger = Ledger::default();
+        let path = directory.join(LEDGER);
+        if path.exists() {
+            for (index, line) in fs::read_to_string(&path)?.lines().enumerate() {
+                let mut parts = line.splitn(2, '\t');
+                match (parts.next(), parts.next()) {
+                    (Some(verdict), Some(answer)) => ledger.guesses.push(Guess {
+                        answer: answer.to_string(),
+                        verdict: verdict.parse()?,
+                    }),
+                    _ => bail!("Malformed line {} in {}", index + 1, path.display()),
+                }
+            }
+        }
+        let confirmed = directory.join(INPUT).with_extension("out");
+        if ledger.correct().is_none() && confirmed.exists() {
+            ledger.guesses.push(Guess {
+                answer: fs::read_to_string(confirmed)?.trim_end().to_string(),
+                verdict: Verdict::Correct,
+            });
+        }
+        Ok(ledger)
+    }
+
+    /// Record the verdict for a submitted answer
+    ///
+    /// A correct answer is also written as the confirmed answer. Recording an answer that
+    /// contradicts what is already known is an error.
+    pub fn record(&self, day: u32, part: u32, answer: &str, verdict: Verdict) -> Result<(), Error> {
+        let answer = answer.trim();
+        if answer.is_empty() || answer.contains('\n') {
+            bail!("Answers must be a single non-empty line");
+        }
+        let ledger = self.ledger(day, part)?;
+        if let Some(guess) = ledger.guesses.iter().find(|guess| guess.answer == answer) {
+            if guess.verdict == verdict {
+                return Ok(());
+            }
+            bail!("{} is already recorded as {}", answer, guess.verdict);
+        }
+        if verdict == Verdict::Correct {
+            if let Some(correct) = ledger.correct() {
+                bail!("{} is already recorded as the correct answer", correct);
+            }
+        }
+
+        let directory = self.existing_directory(day, part)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(directory.join(LEDGER))?;
+        writeln!(file, "{}\t{}", verdict, answer)?;
+        if verdict == Verdict::Correct {
+            fs::write(
+                directory.join(INPUT).with_extension("out"),
+                format!("{}\n", answer),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A store in a temporary directory, which is deleted when the `TempDir` is dropped
+    fn workspace() -> Result<(TempDir, Store), Error> {
+        let root = TempDir::new()?;
+        fs::create_dir_all(root.path().join("day5-1"))?;
+        fs::create_dir_all(root.path().join("day5-2"))?;
+        let store = Store::new(root.path());
+        Ok((root, store))
+    }
+
+    #[test]
+    fn test_input_falls_back_to_part_one() -> Result<(), Error> {
+        let (_root, store) = workspace()?;
+        assert!(store.input(5, 2).is_err());
+        store.save_input(5, 1, "1\n2\n")?;
+        assert_eq!(store.input(5, 2)?, "1\n2\n");
+
+        store.record(5, 2, "42", Verdict::Correct)?;
+        let samples = store.samples(5, 2)?;
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].input, store.directory(5, 1).join("puzzle1.in"));
+        assert_eq!(samples[0].expected()?, Some("42".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ledger() -> Result<(), Error> {
+        let (_root, store) = workspace()?;
+        store.record(5, 1, "100", Verdict::TooLow)?;
+        store.record(5, 1, "300", Verdict::TooHigh)?;
+        store.record(5, 1, "abc", Verdict::Wrong)?;
+        assert!(store.record(5, 1, "100", Verdict::TooHigh).is_err());
+
+        let ledger = store.ledger(5, 1)?;
+        assert_eq!(ledger.guesses.len(), 3);
+        assert!(ledger.check("100").is_err());
+        assert!(ledger.check("abc").is_err());
+        assert!(ledger.check("50").is_err());
+        assert!(ledger.check("301").is_err());
+        assert_eq!(ledger.check("200")?, None);
+
+        store.record(5, 1, "200", Verdict::Correct)?;
+        assert!(store.record(5, 1, "201", Verdict::Correct).is_err());
+        assert_eq!(store.answer(5, 1)?, Some("200".to_string()));
+        let ledger = store.ledger(5, 1)?;
+        assert_eq!(ledger.check("200")?, Some(Verdict::Correct));
+        assert!(ledger.check("201").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_sample() -> Result<(), Error> {
+        let (_root, store) = workspace()?;
+        let first = store.add_sample(5, 1, "1\n", Some("1"))?;
+        let second = store.add_sample(5, 1, "2\n", None)?;
+        assert_eq!(
+            (first.name.as_str(), second.name.as_str()),
+            ("sample1", "sample2")
+        );
+        assert_eq!(first.expected()?, Some("1".to_string()));
+        assert_eq!(second.expected()?, None);
+        assert!(store.add_sample(6, 1, "", None).is_err());
+        Ok(())
+    }
+}
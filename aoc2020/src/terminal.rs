@@ -0,0 +1,217 @@
+use crate::grid::{cell_terminator, GridLike};
+use crate::image::Rgb;
+use crate::position::*;
+use hashbrown::HashMap;
+use std::fmt::Display;
+use std::fmt::Write;
+
+const RESET: &str = "\u{1b}[0m";
+
+fn foreground(colour: Rgb) -> String {
+    format!("\u{1b}[38;2;{};{};{}m", colour.r, colour.g, colour.b)
+}
+
+fn background(colour: Rgb) -> String {
+    format!("\u{1b}[48;2;{};{};{}m", colour.r, colour.g, colour.b)
+}
+
+type ColourFn<'a, T> = Box<dyn Fn(&T) -> Option<Rgb> + 'a>;
+
+/// Renders grids as text for the terminal, with ANSI colours
+///
+/// Compared to the `Display` implementations of the grids, the renderer can show only
+/// a part of the grid, label the axes with the real coordinates, and highlight positions.
+///
+/// # Examples
+///
+/// ```
+/// use aoc2020::image::Rgb;
+/// use aoc2020::position::Position;
+/// use aoc2020::sparse_grid::Grid;
+/// use aoc2020::terminal::Renderer;
+/// let mut grid = Grid::new();
+/// grid.insert(Position::new(-1, 0), '#');
+/// grid.insert(Position::new(1, 1), '#');
+/// let text = Renderer::new().rulers(true).render(&grid);
+/// assert_eq!(text, "  -  \n  101\n0 #  \n1   #\n");
+/// ```
+pub struct Renderer<'a, T> {
+    colour: Option<ColourFn<'a, T>>,
+    viewport: Option<(Position, Position)>,
+    rulers: bool,
+    highlighted: HashMap<Position, Rgb>,
+}
+
+impl<'a, T> Renderer<'a, T>
+where
+    T: Display,
+{
+    pub fn new() -> Renderer<'a, T> {
+        Renderer {
+            colour: None,
+            viewport: None,
+            rulers: false,
+            highlighted: HashMap::new(),
+        }
+    }
+
+    /// Colour the values, `None` means the default terminal colour
+    pub fn colour<F>(mut self, colour: F) -> Renderer<'a, T>
+    where
+        F: Fn(&T) -> Option<Rgb> + 'a,
+    {
+        self.colour = Some(Box::new(colour));
+        self
+    }
+
+    /// Only render the inclusive rectangle between `min` and `max`
+    pub fn viewport(mut self, min: Position, max: Position) -> Renderer<'a, T> {
+        self.viewport = Some((min, max));
+        self
+    }
+
+    /// Label rows and columns with their coordinates, column labels are written vertically
+    pub fn rulers(mut self, rulers: bool) -> Renderer<'a, T> {
+        self.rulers = rulers;
+        self
+    }
+
+    /// Highlight `positions` with a background colour, for example to show a path
+    ///
+    /// Each call can use its own colour, and positions highlighted again get the later
+    /// colour.
+    pub fn highlight<I>(mut self, positions: I, colour: Rgb) -> Renderer<'a, T>
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        self.highlighted
+            .extend(positions.into_iter().map(|position| (position, colour)));
+        self
+    }
+
+    pub fn render<G>(&self, grid: &G) -> String
+    where
+        G: GridLike<Item = T>,
+    {
+        let (min_pos, max_pos) = self.viewport.unwrap_or_else(|| grid.bounds());
+        let width = (min_pos.y..=max_pos.y)
+            .flat_map(|y| (min_pos.x..=max_pos.x).map(move |x| Position::new(x, y)))
+            .filter_map(|position| grid.get(position))
+            .map(|value| format!("{}", value).chars().count())
+            .max()
+            .unwrap_or(1);
+        let terminator = cell_terminator(width);
+
+        let row_labels = (min_pos.y..=max_pos.y)
+            .map(|y| y.to_string())
+            .collect::<Vec<_>>();
+        let label_width = row_labels.iter().map(String::len).max().unwrap_or(0) + 1;
+
+        let mut result = String::new();
+        if self.rulers {
+            let column_labels = (min_pos.x..=max_pos.x)
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>();
+            let height = column_labels.iter().map(String::len).max().unwrap_or(0);
+            for line in 0..height {
+                result.push_str(&" ".repeat(label_width));
+                for label in &column_labels {
+                    let padding = height - label.len();
+                    let ch = if line < padding {
+                        ' '
+                    } else {
+                        label.as_bytes()[line - padding] as char
+                    };
+                    write!(result, "{:>width$}{}", ch, terminator, width = width).unwrap();
+                }
+                result.push('\n');
+            }
+        }
+
+        for (y, label) in (min_pos.y..=max_pos.y).zip(&row_labels) {
+            if self.rulers {
+                write!(result, "{:>width$} ", label, width = label_width - 1).unwrap();
+            }
+            for x in min_pos.x..=max_pos.x {
+                let position = Position::new(x, y);
+                let mut styled = false;
+                if let Some(&colour) = self.highlighted.get(&position) {
+                    result.push_str(&background(colour));
+                    styled = true;
+                }
+                match grid.get(position) {
+                    Some(value) => {
+                        if let Some(colour) = self.colour.as_ref().and_then(|f| f(value)) {
+                            result.push_str(&foreground(colour));
+                            styled = true;
+                        }
+                        write!(result, "{:width$}", value, width = width).unwrap();
+                    }
+                    None => result.push_str(&" ".repeat(width)),
+                }
+                if styled {
+                    result.push_str(RESET);
+                }
+                result.push_str(terminator);
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
+
+impl<'a, T> Default for Renderer<'a, T>
+where
+    T: Display,
+{
+    fn default() -> Self {
+        Renderer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dense_grid::Grid;
+
+    #[test]
+    fn test_viewport_and_highlight() {
+        let mut grid = Grid::from_origo(4, 3);
+        grid[(1, 1)] = 7;
+        grid[(2, 1)] = 12;
+        let text = Renderer::new()
+            .viewport(Position::new(1, 1), Position::new(2, 1))
+            .colour(|&v| if v > 10 { Some(Rgb::RED) } else { None })
+            .highlight(vec![Position::new(1, 1)], Rgb::BLUE)
+            .render(&grid);
+        assert_eq!(
+            text,
+            "\u{1b}[48;2;0;0;255m 7\u{1b}[0m \u{1b}[38;2;255;0;0m12\u{1b}[0m \n"
+        );
+    }
+
+    #[test]
+    fn test_highlight_colours() {
+        let grid: Grid<u8> = Grid::from_origo(3, 1);
+        let text = Renderer::new()
+            .highlight(vec![Position::new(0, 0), Position::new(1, 0)], Rgb::RED)
+            .highlight(vec![Position::new(1, 0), Position::new(2, 0)], Rgb::BLUE)
+            .render(&grid);
+        assert_eq!(
+            text,
+            "\u{1b}[48;2;255;0;0m0\u{1b}[0m\u{1b}[48;2;0;0;255m0\u{1b}[0m\u{1b}[48;2;0;0;255m0\u{1b}[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_wide_rulers() {
+        let mut grid = Grid::from_origo(11, 1);
+        grid[(9, 0)] = 7;
+        grid[(10, 0)] = 12;
+        let text = Renderer::new()
+            .viewport(Position::new(9, 0), Position::new(10, 0))
+            .rulers(true)
+            .render(&grid);
+        assert_eq!(text, "      1 \n   9  0 \n0  7 12 \n");
+    }
+}
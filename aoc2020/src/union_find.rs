@@ -73,3 +73,61 @@ impl UnionFind {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn joins() -> impl Strategy<Value = (i32, Vec<(i32, i32)>)> {
+        (1..40i32).prop_flat_map(|size| {
+            let node = 0..size;
+            (
+                Just(size),
+                proptest::collection::vec((node.clone(), node), 0..60),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn joins_match_naive_labelling((size, joins) in joins()) {
+            let mut union_find = UnionFind::new(size);
+            let mut labels = (0..size).collect::<Vec<_>>();
+            for (a, b) in joins {
+                union_find.join(a, b);
+                let (from, to) = (labels[b as usize], labels[a as usize]);
+                for label in labels.iter_mut().filter(|label| **label == from) {
+                    *label = to;
+                }
+            }
+
+            for a in 0..size {
+                let same_label = labels.iter().filter(|&&l| l == labels[a as usize]).count();
+                prop_assert_eq!(union_find.group_size(a), same_label as i32);
+                for b in 0..size {
+                    prop_assert_eq!(
+                        union_find.find(a) == union_find.find(b),
+                        labels[a as usize] == labels[b as usize]
+                    );
+                }
+            }
+            let mut distinct = labels.clone();
+            distinct.sort();
+            distinct.dedup();
+            prop_assert_eq!(union_find.group_count(), distinct.len() as i32);
+        }
+
+        #[test]
+        fn roots_are_their_own_root((size, joins) in joins()) {
+            let mut union_find = UnionFind::new(size);
+            for (a, b) in joins {
+                union_find.join(a, b);
+            }
+            for node in 0..size {
+                let root = union_find.find(node);
+                prop_assert_eq!(union_find.find(root), root);
+            }
+        }
+    }
+}
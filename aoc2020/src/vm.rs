@@ -0,0 +1,325 @@
+use failure::{bail, format_err, Error};
+use std::fmt;
+use std::str::FromStr;
+use strum_macros::EnumString;
+
+pub type Value = i64;
+
+/// The operations of the machine
+///
+/// New operations are added here and given their effect in `Machine::step`.
+#[derive(EnumString, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Operation {
+    /// Add the argument to the accumulator
+    #[strum(serialize = "acc")]
+    Acc,
+    /// Jump relative to the current instruction
+    #[strum(serialize = "jmp")]
+    Jmp,
+    /// Do nothing
+    #[strum(serialize = "nop")]
+    Nop,
+}
+
+impl Operation {
+    /// The operation that a single corrupted instruction may have been, if any
+    pub fn flipped(self) -> Option<Operation> {
+        match self {
+            Operation::Jmp => Some(Operation::Nop),
+            Operation::Nop => Some(Operation::Jmp),
+            Operation::Acc => None,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operation::Acc => "acc",
+            Operation::Jmp => "jmp",
+            Operation::Nop => "nop",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Instruction {
+    pub operation: Operation,
+    pub argument: Value,
+}
+
+impl Instruction {
+    pub fn new(operation: Operation, argument: Value) -> Instruction {
+        Instruction {
+            operation,
+            argument,
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    /// Parses instructions like `acc +3` and `jmp -4`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(operation), Some(argument), None) => {
+                let operation = operation
+                    .parse()
+                    .map_err(|_| format_err!("Unknown operation \"{}\"", operation))?;
+                let argument = argument.trim_start_matches('+').parse()?;
+                Ok(Instruction::new(operation, argument))
+            }
+            _ => bail!("Could not parse instruction \"{}\"", s),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.operation, self.argument)
+    }
+}
+
+pub type Program = Vec<Instruction>;
+
+/// Parse a program with one instruction per line, skipping empty lines
+pub fn parse_program(input: &str) -> Result<Program, Error> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.parse()
+                .map_err(|err| format_err!("Line {}: {}", index + 1, err))
+        })
+        .collect()
+}
+
+/// How a run of a program ended
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Outcome {
+    /// The program counter moved to just after the last instruction
+    Terminated { accumulator: Value },
+    /// The instruction at `pc` was about to run a second time
+    Loop { accumulator: Value, pc: usize },
+}
+
+impl Outcome {
+    pub fn accumulator(self) -> Value {
+        match self {
+            Outcome::Terminated { accumulator } => accumulator,
+            Outcome::Loop { accumulator, .. } => accumulator,
+        }
+    }
+
+    pub fn terminated(self) -> bool {
+        matches!(self, Outcome::Terminated { .. })
+    }
+}
+
+/// The state of a program being run
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Machine {
+    pub program: Program,
+    pub pc: usize,
+    pub accumulator: Value,
+    /// The number of instructions run
+    pub steps: usize,
+}
+
+impl Machine {
+    pub fn new(program: Program) -> Machine {
+        Machine {
+            program,
+            pc: 0,
+            accumulator: 0,
+            steps: 0,
+        }
+    }
+
+    /// Restart the program from the beginning
+    pub fn reset(&mut self) {
+        self.pc = 0;
+        self.accumulator = 0;
+        self.steps = 0;
+    }
+
+    /// True if the program counter is just after the last instruction
+    pub fn is_terminated(&self) -> bool {
+        self.pc == self.program.len()
+    }
+
+    /// Run the instruction at the program counter
+    pub fn step(&mut self) -> Result<(), Error> {
+        let instruction = match self.program.get(self.pc) {
+            Some(instruction) => *instruction,
+            None => bail!(
+                "Program counter {} is outside the program of {} instructions",
+                self.pc,
+                self.program.len()
+            ),
+        };
+        let mut next = Some(self.pc as Value + 1);
+        match instruction.operation {
+            Operation::Acc => {
+                self.accumulator = self
+                    .accumulator
+                    .checked_add(instruction.argument)
+                    .ok_or_else(|| {
+                        format_err!(
+                            "Instruction {} at {} overflows the accumulator {}",
+                            instruction,
+                            self.pc,
+                            self.accumulator
+                        )
+                    })?;
+            }
+            Operation::Jmp => next = (self.pc as Value).checked_add(instruction.argument),
+            Operation::Nop => {}
+        }
+        let next = match next {
+            Some(next) if 0 <= next && next as usize <= self.program.len() => next,
+            _ => bail!(
+                "Instruction {} at {} jumps outside the program of {} instructions",
+                instruction,
+                self.pc,
+                self.program.len()
+            ),
+        };
+        self.pc = next as usize;
+        self.steps += 1;
+        Ok(())
+    }
+
+    /// Run until the program terminates or an instruction is about to run a second time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use failure::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// use aoc2020::vm::{parse_program, Machine, Outcome};
+    /// let mut machine = Machine::new(parse_program("acc +3\njmp -1\n")?);
+    /// assert_eq!(machine.run()?, Outcome::Loop { accumulator: 3, pc: 0 });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run(&mut self) -> Result<Outcome, Error> {
+        let mut visited = vec![false; self.program.len()];
+        while !self.is_terminated() {
+            // A program counter outside the program is reported by step
+            match visited.get_mut(self.pc) {
+                Some(true) => {
+                    return Ok(Outcome::Loop {
+                        accumulator: self.accumulator,
+                        pc: self.pc,
+                    })
+                }
+                Some(seen) => *seen = true,
+                None => {}
+            }
+            self.step()?;
+        }
+        Ok(Outcome::Terminated {
+            accumulator: self.accumulator,
+        })
+    }
+}
+
+/// A change to one instruction that makes a program terminate
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Repair {
+    /// The index of the changed instruction
+    pub index: usize,
+    pub instruction: Instruction,
+    /// The accumulator when the repaired program terminates
+    pub accumulator: Value,
+}
+
+/// Find the first instruction where flipping `jmp` and `nop` makes the program terminate
+///
+/// Changes that make the program jump outside of itself are skipped.
+pub fn repair(program: &[Instruction]) -> Result<Repair, Error> {
+    let mut machine = Machine::new(program.to_vec());
+    for (index, original) in program.iter().enumerate() {
+        let operation = match original.operation.flipped() {
+            Some(operation) => operation,
+            None => continue,
+        };
+        let instruction = Instruction::new(operation, original.argument);
+        machine.reset();
+        machine.program[index] = instruction;
+        if let Ok(Outcome::Terminated { accumulator }) = machine.run() {
+            return Ok(Repair {
+                index,
+                instruction,
+                accumulator,
+            });
+        }
+        machine.program[index] = *original;
+    }
+    bail!("No single jmp or nop change makes the program terminate")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str =
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+
+    #[test]
+    fn test_parse() -> Result<(), Error> {
+        let program = parse_program(SAMPLE)?;
+        assert_eq!(program.len(), 9);
+        assert_eq!(program[4], Instruction::new(Operation::Jmp, -3));
+        assert_eq!(program[2].to_string(), "jmp +4");
+        assert!("mul +3".parse::<Instruction>().is_err());
+        assert!("acc".parse::<Instruction>().is_err());
+        assert!(parse_program("nop +0\nacc x\n")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Line 2"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_loop_and_repair() -> Result<(), Error> {
+        let program = parse_program(SAMPLE)?;
+        let mut machine = Machine::new(program.clone());
+        assert_eq!(
+            machine.run()?,
+            Outcome::Loop {
+                accumulator: 5,
+                pc: 1
+            }
+        );
+
+        let repair = repair(&program)?;
+        assert_eq!(repair.index, 7);
+        assert_eq!(repair.instruction, Instruction::new(Operation::Nop, -4));
+        assert_eq!(repair.accumulator, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_jump_outside() -> Result<(), Error> {
+        let mut machine = Machine::new(parse_program("nop +0\njmp +5\n")?);
+        assert!(machine.run().is_err());
+        assert_eq!(machine.pc, 1);
+        assert!(repair(&parse_program("acc +1\njmp -1\nacc +2\njmp -1\n")?).is_err());
+
+        let mut machine = Machine::new(parse_program("nop +0\njmp +9223372036854775807\n")?);
+        assert!(machine.run().is_err());
+        let mut machine = Machine::new(parse_program("acc -9223372036854775808\nacc -1\n")?);
+        assert!(machine.run().is_err());
+        assert_eq!(machine.accumulator, Value::MIN);
+        machine.reset();
+        machine.pc = 5;
+        assert!(machine.run().is_err());
+        Ok(())
+    }
+}
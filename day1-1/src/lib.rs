@@ -0,0 +1,56 @@
+#![allow(dead_code, unused_imports)]
+
+use aoc2020::solver::Solver;
+use failure::bail;
+use failure::Error;
+use rayon::prelude::*;
+use strum_macros::EnumString;
+
+use std::char;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::ops::*;
+use std::str;
+use std::str::FromStr;
+
+pub type Input = Vec<i32>;
+pub type Output = i32;
+
+pub fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+    let mut result = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        result.push(line.trim().parse()?)
+    }
+
+    Ok(result)
+}
+
+pub fn solve(input: &mut Input) -> Result<Output, Error> {
+    for a in input.iter() {
+        for b in input.iter() {
+            if a + b == 2020 {
+                return Ok(a * b);
+            }
+        }
+    }
+    bail!("no matching pairs in input")
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    type Input = Input;
+    type Output = Output;
+
+    fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+        read_input(reader)
+    }
+
+    fn solve(input: &mut Input) -> Result<Output, Error> {
+        solve(input)
+    }
+}
@@ -0,0 +1,86 @@
+#![allow(dead_code, unused_imports)]
+
+use aoc2020::solver::Solver;
+use failure::bail;
+use failure::format_err;
+use failure::Error;
+use itertools::Itertools;
+use rayon::prelude::*;
+use strum_macros::EnumString;
+
+use std::char;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::ops::*;
+use std::str;
+use std::str::FromStr;
+
+pub struct Policy {
+    min: i32,
+    max: i32,
+    letter: char,
+}
+
+impl Policy {
+    fn is_valid(&self, password: &str) -> bool {
+        let count = password.chars().filter(|&c| c == self.letter).count() as i32;
+        self.min <= count && count <= self.max
+    }
+}
+
+impl FromStr for Policy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(|ch| ch == '-' || ch == ' ').collect_vec();
+        let policy = Policy {
+            min: parts[0].parse()?,
+            max: parts[1].parse()?,
+            letter: parts[2]
+                .chars()
+                .next()
+                .ok_or_else(|| format_err!("No letter specified"))?,
+        };
+        Ok(policy)
+    }
+}
+
+pub type Input = Vec<(Policy, String)>;
+pub type Output = usize;
+
+pub fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+    let mut result = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let parts = line.split(": ").collect_vec();
+        result.push((parts[0].parse()?, parts[1].to_string()));
+    }
+
+    Ok(result)
+}
+
+pub fn solve(input: &mut Input) -> Result<Output, Error> {
+    let valid = input
+        .iter()
+        .filter(|(policy, password)| policy.is_valid(password))
+        .count();
+    Ok(valid)
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    type Input = Input;
+    type Output = Output;
+
+    fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+        read_input(reader)
+    }
+
+    fn solve(input: &mut Input) -> Result<Output, Error> {
+        solve(input)
+    }
+}
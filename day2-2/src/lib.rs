@@ -0,0 +1,87 @@
+#![allow(dead_code, unused_imports)]
+
+use aoc2020::solver::Solver;
+use failure::bail;
+use failure::format_err;
+use failure::Error;
+use itertools::Itertools;
+use rayon::prelude::*;
+use strum_macros::EnumString;
+
+use std::char;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::ops::*;
+use std::str;
+use std::str::FromStr;
+
+pub struct Policy {
+    pos1: usize,
+    pos2: usize,
+    letter: char,
+}
+
+impl Policy {
+    fn is_valid(&self, password: &str) -> bool {
+        let first = password.chars().nth(self.pos1 - 1).unwrap();
+        let second = password.chars().nth(self.pos2 - 1).unwrap();
+        (first == self.letter) != (second == self.letter)
+    }
+}
+
+impl FromStr for Policy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(|ch| ch == '-' || ch == ' ').collect_vec();
+        let policy = Policy {
+            pos1: parts[0].parse()?,
+            pos2: parts[1].parse()?,
+            letter: parts[2]
+                .chars()
+                .next()
+                .ok_or_else(|| format_err!("No letter specified"))?,
+        };
+        Ok(policy)
+    }
+}
+
+pub type Input = Vec<(Policy, String)>;
+pub type Output = usize;
+
+pub fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+    let mut result = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let parts = line.split(": ").collect_vec();
+        result.push((parts[0].parse()?, parts[1].to_string()));
+    }
+
+    Ok(result)
+}
+
+pub fn solve(input: &mut Input) -> Result<Output, Error> {
+    let valid = input
+        .iter()
+        .filter(|(policy, password)| policy.is_valid(password))
+        .count();
+    Ok(valid)
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    type Input = Input;
+    type Output = Output;
+
+    fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+        read_input(reader)
+    }
+
+    fn solve(input: &mut Input) -> Result<Output, Error> {
+        solve(input)
+    }
+}
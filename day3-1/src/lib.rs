@@ -0,0 +1,62 @@
+#![allow(dead_code, unused_imports)]
+
+use aoc2020::solver::Solver;
+use failure::bail;
+use failure::format_err;
+use failure::Error;
+use itertools::Itertools;
+use rayon::prelude::*;
+use strum_macros::EnumString;
+
+use std::char;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::ops::*;
+use std::str;
+use std::str::FromStr;
+
+pub type Input = Vec<Vec<bool>>;
+pub type Output = usize;
+
+pub fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+    let mut result = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() {
+            result.push(line.chars().map(|ch| ch == '#').collect_vec());
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn solve(input: &mut Input) -> Result<Output, Error> {
+    let mut pos = 0;
+    let mut trees = 0;
+    for line in input {
+        if line[pos % line.len()] {
+            trees += 1;
+        }
+        pos += 3;
+    }
+    Ok(trees)
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    type Input = Input;
+    type Output = Output;
+
+    fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+        read_input(reader)
+    }
+
+    fn solve(input: &mut Input) -> Result<Output, Error> {
+        solve(input)
+    }
+}
@@ -1,32 +1,3 @@
-use assert_cli;
-
-#[test]
-fn sample1() {
-    assert_cli::Assert::main_binary()
-        .stdin(
-            "..##.......
-#...#...#..
-.#....#..#.
-..#.#...#.#
-.#...##..#.
-..#.##.....
-.#.#.#....#
-.#........#
-#.##...#...
-#...##....#
-.#..#...#.#
-",
-        )
-        .stdout()
-        .is("7")
-        .unwrap();
-}
-
-#[test]
-fn puzzle1() {
-    assert_cli::Assert::main_binary()
-        .stdin(include_str!("../data/puzzle1.in"))
-        .stdout()
-        .is("200")
-        .unwrap();
+fn main() {
+    aoc2020::samples::main::<day3_1::Solution>(concat!(env!("CARGO_MANIFEST_DIR"), "/data"));
 }
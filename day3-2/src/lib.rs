@@ -0,0 +1,72 @@
+#![allow(dead_code, unused_imports, clippy::ptr_arg)]
+
+use aoc2020::solver::Solver;
+use failure::bail;
+use failure::format_err;
+use failure::Error;
+use itertools::Itertools;
+use rayon::prelude::*;
+use strum_macros::EnumString;
+
+use std::char;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::io::BufRead;
+use std::ops::*;
+use std::str;
+use std::str::FromStr;
+
+pub type Input = Vec<Vec<bool>>;
+pub type Output = usize;
+
+pub fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+    let mut result = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() {
+            result.push(line.chars().map(|ch| ch == '#').collect_vec());
+        }
+    }
+
+    Ok(result)
+}
+
+fn compute_trees(input: &Input, step_right: usize, step_line: usize) -> usize {
+    let mut pos = 0;
+    let mut trees = 0;
+    for line in (0..input.len()).step_by(step_line) {
+        if input[line][pos % input[line].len()] {
+            trees += 1;
+        }
+        pos += step_right;
+    }
+    trees
+}
+
+pub fn solve(input: &mut Input) -> Result<Output, Error> {
+    let trees = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)]
+        .iter()
+        .map(|(step_right, step_line)| compute_trees(input, *step_right, *step_line))
+        .collect_vec();
+    dbg!(&trees);
+    let result = trees.iter().product();
+    Ok(result)
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    type Input = Input;
+    type Output = Output;
+
+    fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+        read_input(reader)
+    }
+
+    fn solve(input: &mut Input) -> Result<Output, Error> {
+        solve(input)
+    }
+}
@@ -0,0 +1,165 @@
+#![allow(dead_code, unused_imports)]
+
+use aoc2020::solver::Solver;
+use failure::bail;
+use failure::format_err;
+use failure::Error;
+use itertools::Itertools;
+use rayon::prelude::*;
+use strum_macros::EnumString;
+
+use std::char;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::read_to_string;
+use std::io;
+use std::io::BufRead;
+use std::ops::*;
+use std::str;
+use std::str::FromStr;
+
+pub type Input = Vec<String>;
+pub type Output = usize;
+
+pub fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+    let mut result = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        result.push(line.trim().to_string());
+    }
+
+    Ok(result)
+}
+
+pub fn solve(input: &Input) -> Result<Output, Error> {
+    let passport_data: Vec<Vec<&String>> = input
+        .into_iter()
+        .group_by(|v| v.is_empty())
+        .into_iter()
+        .filter(|(empty, _)| !empty)
+        .map(|(_, group)| group.collect_vec())
+        .collect_vec();
+
+    let passports = passport_data
+        .iter()
+        .map(|lines| {
+            lines
+                .iter()
+                .flat_map(|line| {
+                    line.split_ascii_whitespace().map(|kv| {
+                        kv.split(':')
+                            .map(|part| part.to_string())
+                            .collect_tuple::<(String, String)>()
+                            .expect("Format must be valid")
+                    })
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let mut valid = 0;
+
+    for passport in &passports {
+        let is_valid = validate_passport(passport);
+        if is_valid {
+            valid += 1;
+        }
+    }
+
+    Ok(valid)
+}
+
+fn validate_year(value: &str, min: usize, max: usize) -> bool {
+    value.len() == 4 && matches!(value.parse::<usize>(), Ok(year) if min <= year && year <= max)
+}
+
+fn validate_height(value: &str) -> bool {
+    let (min, max) = if value.ends_with("cm") {
+        (150, 193)
+    } else if value.ends_with("in") {
+        (59, 76)
+    } else {
+        return false;
+    };
+    matches!(value[..value.len() - 2].parse::<usize>(), Ok(height) if min <= height && height <= max)
+}
+
+fn validate_field(key: &str, value: &str) -> bool {
+    match key {
+        "byr" => validate_year(value, 1920, 2002),
+        "iyr" => validate_year(value, 2010, 2020),
+        "eyr" => validate_year(value, 2020, 2030),
+        "hgt" => validate_height(value),
+        "hcl" => {
+            value.len() == 7
+                && value.starts_with('#')
+                && value[1..]
+                    .chars()
+                    .all(|ch| matches!(ch, '0'..='9' | 'a'..='f'))
+        }
+        "ecl" => ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&value),
+        "pid" => value.len() == 9 && value.chars().all(|ch| ch.is_ascii_digit()),
+        "cid" => true,
+        _ => false,
+    }
+}
+
+fn validate_passport(passport: &[(String, String)]) -> bool {
+    let data = passport
+        .iter()
+        .cloned()
+        .collect::<HashMap<String, String>>();
+
+    ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
+        .iter()
+        .all(|&key| data.contains_key(key))
+        && data.iter().all(|(key, value)| validate_field(key, value))
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    type Input = Input;
+    type Output = Output;
+
+    fn read_input<R: BufRead>(reader: R) -> Result<Input, Error> {
+        read_input(reader)
+    }
+
+    fn solve(input: &mut Input) -> Result<Output, Error> {
+        solve(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_examples() {
+        assert!(validate_year("2002", 1920, 2002));
+        assert!(!validate_year("2003", 1920, 2002));
+        assert!(!validate_year("02002", 1920, 2002));
+        assert!(validate_height("60in"));
+        assert!(validate_height("190cm"));
+        assert!(!validate_height("190in"));
+        assert!(!validate_height("190"));
+        assert!(validate_field("hcl", "#123abc"));
+        assert!(!validate_field("hcl", "#123abz"));
+        assert!(!validate_field("hcl", "123abc"));
+        assert!(!validate_field("hcl", "#123ABC"));
+        assert!(validate_field("ecl", "brn"));
+        assert!(!validate_field("ecl", "wat"));
+        assert!(validate_field("pid", "000000001"));
+        assert!(!validate_field("pid", "0123456789"));
+    }
+
+    /// Part two only adds rules to part one, whose answer for the same input is 190
+    #[test]
+    fn test_puzzle_bound() -> Result<(), Error> {
+        let input = read_input(include_str!("../data/puzzle1.in").as_bytes())?;
+        assert!(solve(&input)? <= 190);
+        Ok(())
+    }
+}
@@ -1,23 +1,3 @@
-use assert_cli;
-
-#[test]
-fn sample1() {
-    assert_cli::Assert::main_binary()
-        .stdin(
-            "1
-2
-3",
-        )
-        .stdout()
-        .is("6")
-        .unwrap();
-}
-
-#[test]
-fn puzzle1() {
-    assert_cli::Assert::main_binary()
-        .stdin(include_str!("../data/puzzle1.in"))
-        .stdout()
-        .is("6")
-        .unwrap();
+fn main() {
+    aoc2020::samples::main::<example_day::Solution>(concat!(env!("CARGO_MANIFEST_DIR"), "/data"));
 }
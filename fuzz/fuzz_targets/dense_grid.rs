@@ -0,0 +1,11 @@
+#![no_main]
+use aoc2020::dense_grid::Grid;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(grid) = input.parse::<Grid<u32>>() {
+        let reparsed = grid.to_string().parse::<Grid<u32>>().unwrap();
+        assert_eq!(reparsed, grid);
+    }
+    let _ = input.parse::<Grid<char>>();
+});
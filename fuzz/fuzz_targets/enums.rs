@@ -0,0 +1,14 @@
+#![no_main]
+use aoc2020::grid::Connectivity;
+use aoc2020::position::{Cardinal, Compass, Direction, Turn};
+use aoc2020::vm::parse_program;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<Cardinal>();
+    let _ = input.parse::<Direction>();
+    let _ = input.parse::<Turn>();
+    let _ = input.parse::<Compass>();
+    let _ = input.parse::<Connectivity>();
+    let _ = parse_program(input);
+});
@@ -0,0 +1,16 @@
+#![no_main]
+use aoc2020::expr::{parse, Precedence};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for precedence in &[
+        Precedence::equal(),
+        Precedence::addition_first(),
+        Precedence::standard(),
+    ] {
+        if let Ok(expr) = parse(input, precedence) {
+            let _ = expr.evaluate::<u64>();
+            let _ = expr.evaluate::<i128>();
+        }
+    }
+});
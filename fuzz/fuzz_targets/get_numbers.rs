@@ -0,0 +1,10 @@
+#![no_main]
+use aoc2020::input::{get_numbers, get_words};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = get_numbers::<i32>(input);
+    let _ = get_numbers::<u8>(input);
+    let _ = get_numbers::<i128>(input);
+    let _ = get_words(input);
+});
@@ -0,0 +1,19 @@
+#![no_main]
+use aoc2020::matrix::Matrix;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(matrix) = input.parse::<Matrix>() {
+        if matrix.width > 0 {
+            let reparsed = matrix.to_string().parse::<Matrix>().unwrap();
+            assert_eq!(reparsed, matrix);
+            assert_eq!(matrix.rot90().rot90().rot90().rot90(), matrix);
+            for y in 0..matrix.height {
+                matrix.row(y);
+            }
+            for x in 0..matrix.width {
+                matrix.col(x);
+            }
+        }
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+use aoc2020::sparse_grid::Grid;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(grid) = input.parse::<Grid<u32>>() {
+        grid.to_string();
+        grid.iter().count();
+    }
+    let _ = input.parse::<Grid<char>>();
+});